@@ -0,0 +1,176 @@
+use std::any::Any;
+use std::borrow::{Borrow, Cow};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+
+use crate::global::GlobalPool;
+use crate::{Poolable, Pooled};
+
+/// The number of entries each thread caches for each [`GlobalPool`].
+pub const THREAD_CACHE_ENTRIES: usize = 64;
+
+type FastState = BuildHasherDefault<FastHasher>;
+
+thread_local! {
+    /// The per-thread caches, keyed by the address of the [`GlobalPool`]
+    /// they belong to.
+    static CACHES: RefCell<HashMap<usize, Box<dyn Any>, FastState>> =
+        RefCell::new(HashMap::with_hasher(FastState::default()));
+}
+
+/// A fast, non-cryptographic hasher in the style of `FxHasher`.
+///
+/// The cache is only ever accessed by its own thread, and a poorly
+/// distributed hash can only cause cache misses, so a resistance to collision
+/// attacks isn't needed.
+#[derive(Default)]
+struct FastHasher(u64);
+
+impl FastHasher {
+    const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+    fn add(&mut self, word: u64) {
+        self.0 = (self.0.rotate_left(5) ^ word).wrapping_mul(Self::SEED);
+    }
+}
+
+impl Hasher for FastHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            let mut word = [0; 8];
+            word.copy_from_slice(chunk);
+            self.add(u64::from_le_bytes(word));
+        }
+        let remainder = chunks.remainder();
+        if !remainder.is_empty() {
+            let mut word = [0; 8];
+            word[..remainder.len()].copy_from_slice(remainder);
+            self.add(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.add(u64::from(value));
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.add(value);
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.add(value as u64);
+    }
+}
+
+/// A direct-mapped cache of recently retrieved values from a single
+/// [`GlobalPool`].
+///
+/// Each cached entry is a strong reference, which keeps the cached values
+/// allocated in the pool until they are evicted or the thread exits.
+struct ThreadCache<T, S>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd + 'static,
+    S: BuildHasher + 'static,
+{
+    entries: Vec<Option<Pooled<&'static GlobalPool<T, S>, S>>>,
+}
+
+impl<T, S> ThreadCache<T, S>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher,
+{
+    fn new() -> Self {
+        Self {
+            entries: vec![None; THREAD_CACHE_ENTRIES],
+        }
+    }
+
+    fn entry_for(value: &T::Borrowed) -> usize {
+        // The upper bits are used, as they are affected by every bit of the
+        // value.
+        #[allow(clippy::cast_possible_truncation)] // Only 32 bits remain after the shift.
+        let hash = (FastState::default().hash_one(value) >> 32) as usize;
+        hash % THREAD_CACHE_ENTRIES
+    }
+}
+
+fn address_of<T, S>(pool: &'static GlobalPool<T, S>) -> usize
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher,
+{
+    std::ptr::addr_of!(*pool) as usize
+}
+
+pub fn get<T, S>(
+    pool: &'static GlobalPool<T, S>,
+    value: Cow<'_, T::Borrowed>,
+) -> Pooled<&'static GlobalPool<T, S>, S>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher,
+{
+    let index = ThreadCache::<T, S>::entry_for(&value);
+    let lookup = CACHES.try_with(|caches| {
+        let mut caches = caches.borrow_mut();
+        let cache = caches
+            .entry(address_of(pool))
+            .or_insert_with(|| Box::new(ThreadCache::<T, S>::new()))
+            .downcast_mut::<ThreadCache<T, S>>()?;
+        match &cache.entries[index] {
+            Some(entry) if Borrow::<T::Borrowed>::borrow(&**entry) == &*value => {
+                Some(Some(entry.clone()))
+            }
+            _ => Some(None),
+        }
+    });
+
+    match lookup {
+        Ok(Some(Some(hit))) => {
+            #[cfg(feature = "stats")]
+            pool.record_cached_hit();
+            hit
+        }
+        Ok(Some(None)) => {
+            let pooled = pool.get(value);
+            let evicted = CACHES.with(|caches| {
+                let mut caches = caches.borrow_mut();
+                caches
+                    .get_mut(&address_of(pool))
+                    .and_then(|cache| cache.downcast_mut::<ThreadCache<T, S>>())
+                    .and_then(|cache| cache.entries[index].replace(pooled.clone()))
+            });
+            // The evicted value is dropped after the cache is no longer
+            // borrowed, as dropping it may need to lock the pool.
+            drop(evicted);
+            pooled
+        }
+        // The thread is exiting and its cache has already been destroyed, or
+        // the cache stored for this pool's address belongs to another type of
+        // pool. The latter can't happen, as pools are never moved or dropped,
+        // but looking the value up in the pool is always correct.
+        Ok(None) | Err(_) => pool.get(value),
+    }
+}
+
+pub fn clear<T, S>(pool: &'static GlobalPool<T, S>)
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher,
+{
+    let removed = CACHES
+        .try_with(|caches| caches.borrow_mut().remove(&address_of(pool)))
+        .ok()
+        .flatten();
+    // Dropping the cache releases its entries, which may need to lock the
+    // pool.
+    drop(removed);
+}
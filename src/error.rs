@@ -0,0 +1,76 @@
+use std::collections::TryReserveError;
+use std::ffi::NulError;
+use std::fmt::Display;
+
+/// An error returned by the fallible functions in this crate.
+///
+/// Functions that return this error report failures instead of panicking.
+/// [`Error::WouldBlock`] is only returned by functions that never wait for a
+/// pool's lock, such as `try_get()`. Functions such as `get_checked()` wait
+/// for the lock instead.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The pool is currently locked, and the operation would need to wait for
+    /// the lock to be released.
+    WouldBlock,
+    /// The pool was unable to grow its tables to hold a new value.
+    ///
+    /// Only the pool's own tables are grown fallibly. As with any other
+    /// allocation, failing to allocate the value itself aborts the process.
+    OutOfMemory(TryReserveError),
+    /// Inserting the value would exceed the pool's
+    /// [`max_entries`](crate::PoolLimits::max_entries) limit.
+    TooManyEntries,
+    /// Inserting the value would exceed the pool's
+    /// [`max_bytes`](crate::PoolLimits::max_bytes) limit.
+    TooManyBytes,
+    /// The value exceeds the pool's
+    /// [`max_value_len`](crate::PoolLimits::max_value_len) limit.
+    ValueTooLong,
+    /// A value's index in its pool does not fit in a [`Symbol`](crate::Symbol).
+    SymbolOverflow,
+    /// A string could not be converted into a C string because it contains an
+    /// interior NUL byte.
+    InteriorNul(NulError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::WouldBlock => f.write_str("the pool is locked"),
+            Error::OutOfMemory(err) => write!(f, "out of memory: {err}"),
+            Error::TooManyEntries => f.write_str("the pool's entry limit has been reached"),
+            Error::TooManyBytes => f.write_str("the pool's byte limit has been reached"),
+            Error::ValueTooLong => f.write_str("the value exceeds the pool's length limit"),
+            Error::SymbolOverflow => f.write_str("pool index exceeds u32::MAX"),
+            Error::InteriorNul(err) => Display::fmt(err, f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::OutOfMemory(err) => Some(err),
+            Error::InteriorNul(err) => Some(err),
+            Error::WouldBlock
+            | Error::TooManyEntries
+            | Error::TooManyBytes
+            | Error::ValueTooLong
+            | Error::SymbolOverflow => None,
+        }
+    }
+}
+
+impl From<TryReserveError> for Error {
+    fn from(err: TryReserveError) -> Self {
+        Self::OutOfMemory(err)
+    }
+}
+
+impl From<NulError> for Error {
+    fn from(err: NulError) -> Self {
+        Self::InteriorNul(err)
+    }
+}
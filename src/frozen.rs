@@ -0,0 +1,153 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::hash::BuildHasher;
+
+use crate::pool::{Borrowed, LookupKey, Pool, SharedData};
+use crate::{Error, PoolKind, Pooled};
+
+/// An immutable snapshot of a pool that can be read without locking.
+///
+/// A [`FrozenPool`] is created using `SharedPool::freeze()` or
+/// `GlobalPool::freeze()`. It contains every value that was in the pool when it
+/// was frozen, and lookups return the same [`Pooled`] values that the pool
+/// returns. This means values retrieved from a [`FrozenPool`] are
+/// [`Pooled::ptr_eq()`] to values retrieved from the original pool, whether
+/// they were created before or after the pool was frozen.
+///
+/// The frozen pool holds a reference to each value it contains, keeping them
+/// allocated until the frozen pool is dropped. Values that are inserted into
+/// the original pool after it was frozen are not visible to
+/// [`FrozenPool::get()`], but [`FrozenPool::get_or_insert()`] can be used to
+/// fall back to the original pool.
+///
+/// ```rust
+/// use interner::shared::{SharedString, StringPool};
+///
+/// let pool = StringPool::default();
+/// let hello = pool.get("hello");
+///
+/// let frozen = pool.freeze();
+/// assert!(SharedString::ptr_eq(&frozen.get("hello").unwrap(), &hello));
+/// assert!(frozen.get("world").is_none());
+///
+/// // Unknown values can still be inserted into the original pool.
+/// let world = frozen.get_or_insert("world");
+/// assert!(SharedString::ptr_eq(&world, &pool.get("world")));
+/// ```
+pub struct FrozenPool<P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    pool: P,
+    active: HashSet<SharedData<P, S>, S>,
+}
+
+impl<P, S> FrozenPool<P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    pub(crate) fn new(pool: P, symbols: &Pool<P, S>) -> Self
+    where
+        S: Clone,
+    {
+        Self {
+            pool,
+            active: symbols.active.clone(),
+        }
+    }
+
+    /// Returns a copy of the [`Pooled`] value for `value` if it was in the
+    /// pool when it was frozen. This function never locks the pool.
+    #[must_use]
+    pub fn get(&self, value: &Borrowed<P, S>) -> Option<Pooled<P, S>> {
+        let key: &dyn LookupKey<Borrowed<P, S>> = &value;
+        self.active.get(key).cloned().map(Pooled::new)
+    }
+
+    /// Returns a copy of the [`Pooled`] value for `value`. If `value` was not
+    /// in the pool when it was frozen, the original pool is locked and the
+    /// value is retrieved or inserted using the original pool.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if inserting the value would exceed the original
+    /// pool's [limits](crate::PoolLimits).
+    /// [`FrozenPool::try_get_or_insert()`] returns an error instead.
+    #[must_use]
+    pub fn get_or_insert<'a, V>(&self, value: V) -> Pooled<P, S>
+    where
+        V: Into<Cow<'a, Borrowed<P, S>>>,
+        Borrowed<P, S>: 'a,
+    {
+        match self.try_get_or_insert(value) {
+            Ok(pooled) => pooled,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Returns a copy of the [`Pooled`] value for `value`, inserting it into
+    /// the original pool if needed.
+    ///
+    /// This function behaves like [`FrozenPool::get_or_insert()`], except that
+    /// it returns an error instead of panicking. It waits for the original
+    /// pool's lock if another thread holds it.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OutOfMemory`]: The pool could not grow its tables to hold
+    ///   `value`.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting `value` would exceed the original
+    ///   pool's [limits](crate::PoolLimits).
+    pub fn try_get_or_insert<'a, V>(&self, value: V) -> Result<Pooled<P, S>, Error>
+    where
+        V: Into<Cow<'a, Borrowed<P, S>>>,
+        Borrowed<P, S>: 'a,
+    {
+        let value = value.into();
+        match self.get(&value) {
+            Some(pooled) => Ok(pooled),
+            None => self
+                .pool
+                .with_active_symbols(|symbols| symbols.try_get(value, &self.pool)),
+        }
+    }
+
+    /// Returns true if `value` was in the pool when it was frozen. This
+    /// function never locks the pool.
+    #[must_use]
+    pub fn contains(&self, value: &Borrowed<P, S>) -> bool {
+        let key: &dyn LookupKey<Borrowed<P, S>> = &value;
+        self.active.contains(key)
+    }
+
+    /// Returns the number of values in this frozen pool.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    /// Returns true if this frozen pool contains no values.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Returns an iterator over the values in this frozen pool.
+    pub fn iter(&self) -> impl Iterator<Item = &P::Pooled> + '_ {
+        self.active.iter().map(|data| &data.0.value)
+    }
+}
+
+impl<P, S> Debug for FrozenPool<P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
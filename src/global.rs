@@ -1,12 +1,31 @@
-use std::borrow::Cow;
+#[cfg(feature = "track-holders")]
+use std::backtrace::Backtrace;
+use std::borrow::{Borrow, Cow};
 use std::collections::hash_map::RandomState;
-use std::fmt::Debug;
+use std::ffi::{CStr, CString, NulError, OsStr, OsString};
+use std::fmt::{Debug, Display};
 use std::hash::{BuildHasher, Hash};
 use std::path::{Path, PathBuf};
-use std::sync::{Mutex, OnceLock};
+#[cfg(feature = "registry")]
+use std::sync::atomic::AtomicBool;
+#[cfg(feature = "stats")]
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, TryLockError};
 
-use crate::pool::{Pool, PoolKindSealed, Poolable};
-use crate::{PoolKind, Pooled};
+use crate::cache;
+#[cfg(feature = "stats")]
+use crate::pool::PoolStats;
+use crate::pool::{
+    self, AtomicLimits, MemoryUsage, Observers, Pool, PoolEvent, PoolGuard, PoolKindSealed,
+    ReleaseQueue, ShardTotals,
+};
+#[cfg(feature = "registry")]
+use crate::registry::{self, Kind, PoolInfo, Registered};
+use crate::{
+    Entry, Error, FrozenPool, PoolKind, PoolLimits, PoolSnapshot, Poolable, Pooled, ReleaseMode,
+    Symbol, VacantEntry,
+};
 
 /// A pooled string that is stored in a [`GlobalPool`].
 ///
@@ -16,6 +35,17 @@ pub type GlobalString<S = RandomState> = Pooled<&'static GlobalPool<String, S>,
 ///
 /// This type implements `From<PathBuf>` and `From<&Path>`.
 pub type GlobalPath<S = RandomState> = Pooled<&'static GlobalPool<PathBuf, S>, S>;
+/// A pooled OS string that is stored in a [`GlobalPool`].
+///
+/// This type implements `From<OsString>` and `From<&OsStr>`.
+pub type GlobalOsString<S = RandomState> = Pooled<&'static GlobalPool<OsString, S>, S>;
+/// A pooled C string that is stored in a [`GlobalPool`].
+///
+/// The pooled value is stored as a `Box<CStr>`, which never moves while any
+/// copies of the pooled value are still allocated. This means the pointer
+/// returned by [`CStr::as_ptr()`] remains a valid NUL-terminated string for as
+/// long as the [`GlobalCString`] it was retrieved from is alive.
+pub type GlobalCString<S = RandomState> = Pooled<&'static GlobalPool<CString, S>, S>;
 /// A pooled buffer (`Vec<u8>`) that is stored in a [`GlobalPool`].
 ///
 /// This type implements `From<Vec<u8>>` and `From<&[u8]>`.
@@ -28,6 +58,17 @@ pub type StringPool<S = RandomState> = GlobalPool<String, S>;
 /// Each [`PathPool`] has its own storage. When comparing [`GlobalPath`]s
 /// from separate pools, the full string comparison function must be used.
 pub type PathPool<S = RandomState> = GlobalPool<PathBuf, S>;
+/// A global OS string interning pool that manages [`GlobalOsString`]s.
+///
+/// Each [`OsStringPool`] has its own storage. When comparing
+/// [`GlobalOsString`]s from separate pools, the full string comparison function
+/// must be used.
+pub type OsStringPool<S = RandomState> = GlobalPool<OsString, S>;
+/// A global C string interning pool that manages [`GlobalCString`]s.
+///
+/// Each [`CStringPool`] has its own storage. When comparing [`GlobalCString`]s
+/// from separate pools, the full string comparison function must be used.
+pub type CStringPool<S = RandomState> = GlobalPool<CString, S>;
 /// A global byte buffer interning pool that manages [`GlobalBuffer`]s.
 ///
 /// Each [`BufferPool`] has its own storage. When comparing [`GlobalBuffer`]s
@@ -48,11 +89,24 @@ pub type BufferPool<S = RandomState> = GlobalPool<Vec<u8>, S>;
 ///
 /// assert!(GlobalString::ptr_eq(&interned, &second));
 /// ```
-#[derive(Debug)]
-pub struct GlobalPool<T, S = RandomState>(Mutex<GlobalPoolState<T, S>>)
+pub struct GlobalPool<T, S = RandomState>
 where
     T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd + 'static,
-    S: BuildHasher + 'static;
+    S: BuildHasher + 'static,
+{
+    state: Mutex<GlobalPoolState<T, S>>,
+    limits: AtomicLimits,
+    observers: Observers<&'static GlobalPool<T, S>, S>,
+    releases: ReleaseQueue<&'static GlobalPool<T, S>, S>,
+    name: Option<&'static str>,
+    /// The number of lookups served by [`GlobalPool::get_cached()`] without
+    /// locking the pool.
+    #[cfg(feature = "stats")]
+    cached_hits: AtomicU64,
+    /// Whether this pool has been added to the registry.
+    #[cfg(feature = "registry")]
+    registered: AtomicBool,
+}
 
 #[derive(Debug)]
 enum GlobalPoolState<T, S>
@@ -67,6 +121,78 @@ where
     Initialized(Pool<&'static GlobalPool<T, S>, S>),
 }
 
+impl<T, S> GlobalPoolState<T, S>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher,
+{
+    /// Returns the pool, initializing it if this is its first use. This state
+    /// must belong to `pool`.
+    ///
+    /// If the hasher's initialization function panics, the state is left
+    /// unchanged so that initialization is attempted again on next use.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn initialized(
+        &mut self,
+        pool: &'static GlobalPool<T, S>,
+    ) -> &mut Pool<&'static GlobalPool<T, S>, S> {
+        if !matches!(self, GlobalPoolState::Initialized(_)) {
+            #[cfg(feature = "tracing")]
+            let _span =
+                tracing::debug_span!("initialize", pool = pool.name.unwrap_or_default()).entered();
+            let (capacity, hasher) = match std::mem::replace(self, GlobalPoolState::Initializing) {
+                GlobalPoolState::LazyInitialize { capacity, hasher } => {
+                    // Restore the state in case the hasher panics.
+                    *self = GlobalPoolState::LazyInitialize { capacity, hasher };
+                    (capacity, hasher())
+                }
+                GlobalPoolState::StaticInitialize { capacity, hasher } => (capacity, hasher),
+                GlobalPoolState::Initializing | GlobalPoolState::Initialized(_) => {
+                    unreachable!("invalid state")
+                }
+            };
+            // The pool is stored before reserving its capacity, which ensures
+            // the state remains valid if reserving panics.
+            *self = GlobalPoolState::Initialized(Pool::with_capacity_and_hasher(0, hasher));
+            if let GlobalPoolState::Initialized(symbols) = self {
+                symbols.reserve(capacity);
+                #[cfg(feature = "tracing")]
+                tracing::debug!(
+                    pool = pool.name.unwrap_or_default(),
+                    capacity = symbols.capacity(),
+                    "initialized pool"
+                );
+            }
+        }
+
+        let GlobalPoolState::Initialized(pool) = self else {
+            unreachable!("always initialized above")
+        };
+        pool
+    }
+}
+
+impl<T, S> PoolGuard<&'static GlobalPool<T, S>, S> for MutexGuard<'_, GlobalPoolState<T, S>>
+where
+    &'static GlobalPool<T, S>: PoolKind<S>,
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd + 'static,
+    S: BuildHasher + 'static,
+{
+    fn symbols(&self) -> &Pool<&'static GlobalPool<T, S>, S> {
+        let GlobalPoolState::Initialized(pool) = &**self else {
+            unreachable!("initialized before the guard is used")
+        };
+        pool
+    }
+
+    fn symbols_mut(&mut self) -> &mut Pool<&'static GlobalPool<T, S>, S> {
+        let GlobalPoolState::Initialized(pool) = &mut **self else {
+            unreachable!("initialized before the guard is used")
+        };
+        pool
+    }
+}
+
 impl<T> GlobalPool<T>
 where
     T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
@@ -76,13 +202,42 @@ where
     pub const fn new() -> Self {
         Self::with_capacity_and_hasher_init(0, RandomState::new)
     }
+
+    /// Returns a new instance named `name` using [`RandomState`] for the
+    /// internal hashing. See [`GlobalPool::with_name()`] for more information.
+    ///
+    /// ```rust
+    /// use interner::global::GlobalPool;
+    ///
+    /// static IDENTIFIERS: GlobalPool<String> = GlobalPool::named("identifiers");
+    ///
+    /// assert_eq!(IDENTIFIERS.name(), Some("identifiers"));
+    /// ```
+    #[must_use]
+    pub const fn named(name: &'static str) -> Self {
+        Self::new().with_name(name)
+    }
+}
+
+impl<T> Default for GlobalPool<T>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+{
+    fn default() -> Self {
+        Self::new()
+    }
 }
+
 impl<T, S> GlobalPool<T, S>
 where
     T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
     S: BuildHasher,
 {
     /// Returns a collection of the currently pooled items.
+    ///
+    /// Values that were made permanent can be identified using
+    /// [`Pooled::is_permanent()`]. To visit each value without creating
+    /// [`Pooled`] handles, use [`GlobalPool::for_each()`] or [`GlobalPool::fold()`].
     #[must_use]
     pub fn pooled<C>(&'static self) -> C
     where
@@ -91,10 +246,65 @@ where
         self.with_active_symbols(|pool| {
             pool.active
                 .iter()
-                .map(|data| Pooled(data.clone()))
+                .map(|data| Pooled::new(data.clone()))
                 .collect()
         })
     }
+
+    /// Returns a report of the values in this pool that are still referenced.
+    ///
+    /// Values that are only referenced by `StaticPooled*` values, such as a
+    /// [`StaticPooledString`], and values that were made permanent are not
+    /// included, as they are expected to remain in the pool for the duration
+    /// of the process. Deferred releases are collected before the report is
+    /// created.
+    ///
+    /// Values cached by [`GlobalPool::get_cached()`] are referenced by each
+    /// thread's cache. Calling [`GlobalPool::clear_thread_cache()`] releases
+    /// the current thread's references.
+    ///
+    /// ```rust
+    /// use interner::global::{StaticPooledString, StringPool};
+    ///
+    /// static STRINGS: StringPool = StringPool::new();
+    /// static GREETING: StaticPooledString = STRINGS.get_static("hello");
+    ///
+    /// assert_eq!(*GREETING, "hello");
+    /// STRINGS.leak_report().assert_empty();
+    ///
+    /// let world = STRINGS.get("world");
+    /// let report = STRINGS.leak_report();
+    /// assert_eq!(report.entries().len(), 1);
+    /// assert_eq!(&*report.entries()[0].value, "world");
+    /// assert_eq!(report.entries()[0].strong_count, 1);
+    /// report.excluding(&["world"]).assert_empty();
+    /// ```
+    #[must_use]
+    pub fn leak_report(&'static self) -> LeakReport<T> {
+        self.with_active_symbols(|symbols| {
+            symbols.collect(&self.releases);
+            let mut entries = symbols
+                .active
+                .iter()
+                .filter(|data| !data.0.permanent.load(Ordering::Relaxed))
+                .filter_map(|data| {
+                    // The pool holds two references to each value.
+                    let strong_count = Arc::strong_count(&data.0)
+                        .saturating_sub(2 + data.0.statics.load(Ordering::Relaxed));
+                    (strong_count > 0).then(|| LeakedValue {
+                        value: data.0.value.clone(),
+                        index: data.0.index,
+                        strong_count,
+                    })
+                })
+                .collect::<Vec<_>>();
+            entries.sort_unstable_by_key(|entry| entry.index);
+            LeakReport {
+                name: self.name,
+                entries,
+            }
+        })
+    }
 }
 impl<T, S, S2> PartialEq<GlobalPool<T, S2>> for GlobalPool<T, S>
 where
@@ -107,6 +317,44 @@ where
     }
 }
 
+impl<T, S> Debug for GlobalPool<T, S>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("GlobalPool");
+        debug.field("name", &self.name);
+        // The pool is never waited for, as it may be locked by this thread.
+        if let Ok(state) = self.state.try_lock() {
+            if let GlobalPoolState::Initialized(pool) = &*state {
+                debug.field("len", &pool.len());
+            }
+        }
+        debug.finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "registry")]
+impl<T, S> Registered for &'static GlobalPool<T, S>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    T::Boxed: Send + Sync,
+    S: BuildHasher + Send + Sync,
+{
+    fn info(self: Arc<Self>) -> PoolInfo {
+        let (len, memory) =
+            self.with_active_symbols(|symbols| (symbols.len(), symbols.memory_usage()));
+        PoolInfo {
+            name: self.name,
+            kind: Kind::Global,
+            value_type: std::any::type_name::<T>(),
+            len,
+            memory,
+        }
+    }
+}
+
 impl<T, S> PoolKindSealed<S> for &'static GlobalPool<T, S>
 where
     T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
@@ -116,27 +364,50 @@ where
     type Pooled = T::Boxed;
 
     fn with_active_symbols<R>(&self, logic: impl FnOnce(&mut Pool<Self, S>) -> R) -> R {
-        let mut symbols = self.0.lock().expect("poisoned");
-        if !matches!(*symbols, GlobalPoolState::Initialized(_)) {
-            let pool = match std::mem::replace(&mut *symbols, GlobalPoolState::Initializing) {
-                GlobalPoolState::LazyInitialize { capacity, hasher } => {
-                    Pool::with_capacity_and_hasher(capacity, hasher())
-                }
-                GlobalPoolState::StaticInitialize { capacity, hasher } => {
-                    Pool::with_capacity_and_hasher(capacity, hasher)
-                }
+        let state = pool::lock(self, &self.state);
+        pool::with_locked(self, state, |state| state.initialized(self), logic)
+    }
 
-                _ => unreachable!("invalid state"),
-            };
-            *symbols = GlobalPoolState::Initialized(pool);
-        }
+    fn try_with_active_symbols<R>(&self, logic: impl FnOnce(&mut Pool<Self, S>) -> R) -> Option<R> {
+        let state = match self.state.try_lock() {
+            Ok(state) => state,
+            Err(TryLockError::WouldBlock) => return None,
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        };
+        Some(pool::with_locked(
+            self,
+            state,
+            |state| state.initialized(self),
+            logic,
+        ))
+    }
+
+    fn enforced_limits(&self) -> Option<&AtomicLimits> {
+        Some(&self.limits)
+    }
+
+    fn release_queue(&self) -> &ReleaseQueue<Self, S> {
+        &self.releases
+    }
+
+    fn observers(&self) -> Option<&Observers<Self, S>> {
+        Some(&self.observers)
+    }
+
+    fn pool_name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    fn shard_totals(&self) -> Option<&ShardTotals> {
+        None
+    }
 
-        let GlobalPoolState::Initialized(pool) = &mut *symbols else { unreachable!("always initialized above") };
-        logic(pool)
+    fn clone_for_value(&self) -> Self {
+        self
     }
 
     fn address_of(&self) -> *const () {
-        std::ptr::addr_of!(*self).cast()
+        std::ptr::addr_of!(**self).cast()
     }
 }
 
@@ -166,40 +437,582 @@ where
     /// Returns a new instance using the provided hasher with enough capacity to
     /// hold the requested number of items without reallocating.
     pub const fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
-        Self(Mutex::new(GlobalPoolState::StaticInitialize {
-            capacity,
-            hasher,
-        }))
+        Self {
+            state: Mutex::new(GlobalPoolState::StaticInitialize { capacity, hasher }),
+            limits: AtomicLimits::new(PoolLimits::new()),
+            observers: Observers::new(),
+            releases: ReleaseQueue::new(),
+            name: None,
+            #[cfg(feature = "stats")]
+            cached_hits: AtomicU64::new(0),
+            #[cfg(feature = "registry")]
+            registered: AtomicBool::new(false),
+        }
     }
 
     /// Returns a new instance using the function to load the hasher when the
     /// pool is initialized on first use. The returned instance has enough
     /// capacity to hold the requested number of items without reallocating.
     pub const fn with_capacity_and_hasher_init(capacity: usize, init: fn() -> S) -> Self {
-        Self(Mutex::new(GlobalPoolState::LazyInitialize {
-            capacity,
-            hasher: init,
-        }))
+        Self {
+            state: Mutex::new(GlobalPoolState::LazyInitialize {
+                capacity,
+                hasher: init,
+            }),
+            limits: AtomicLimits::new(PoolLimits::new()),
+            observers: Observers::new(),
+            releases: ReleaseQueue::new(),
+            name: None,
+            #[cfg(feature = "stats")]
+            cached_hits: AtomicU64::new(0),
+            #[cfg(feature = "registry")]
+            registered: AtomicBool::new(false),
+        }
     }
-}
 
-impl<S> GlobalPool<String, S>
-where
-    S: BuildHasher,
-{
-    /// Returns a copy of an existing [`GlobalString`] if one is found.
-    /// Otherwise, a new [`GlobalString`] is created and returned.
+    /// Returns this pool after setting the limits that are enforced when
+    /// inserting new values. See [`GlobalPool::set_limits()`] for more
+    /// information.
+    ///
+    /// ```rust
+    /// use interner::global::GlobalPool;
+    /// use interner::PoolLimits;
+    ///
+    /// static STRINGS: GlobalPool<String> =
+    ///     GlobalPool::new().with_limits(PoolLimits::new().with_max_bytes(1024));
+    ///
+    /// assert!(STRINGS.try_get("hello").is_ok());
+    /// ```
+    #[must_use]
+    pub const fn with_limits(mut self, limits: PoolLimits) -> Self {
+        self.limits = AtomicLimits::new(limits);
+        self
+    }
+
+    /// Returns this pool after naming it `name`.
+    ///
+    /// The name is included in this pool's [`Debug`] output, and in its
+    /// `tracing` events when the `tracing` feature is enabled, and it is
+    /// reported by [`registry::pools()`](crate::registry::pools) once the pool
+    /// is registered using `GlobalPool::register()`.
+    #[must_use]
+    pub const fn with_name(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Returns the name given to this pool using [`GlobalPool::with_name()`].
+    #[must_use]
+    pub const fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    /// Adds this pool to the pools listed by
+    /// [`registry::pools()`](crate::registry::pools). Registering a pool more
+    /// than once has no effect.
+    ///
+    /// Listing a pool that has not been used yet initializes it.
+    ///
+    /// ```rust
+    /// use interner::global::GlobalPool;
+    /// use interner::registry;
+    ///
+    /// static IDENTIFIERS: GlobalPool<String> = GlobalPool::named("identifiers");
     ///
-    /// While any copies of the returned [`GlobalString`] are still allocated,
-    /// calling this function is guaranteed to return a copy of the same string.
-    pub fn get<'a, V>(&'static self, value: V) -> GlobalString<S>
+    /// IDENTIFIERS.register();
+    /// assert!(registry::pools()
+    ///     .iter()
+    ///     .any(|info| info.name == Some("identifiers")));
+    /// ```
+    #[cfg(feature = "registry")]
+    pub fn register(&'static self)
     where
-        V: Into<Cow<'a, str>>,
+        T::Boxed: Send + Sync,
+        S: Send + Sync,
+    {
+        if !self.registered.swap(true, Ordering::Relaxed) {
+            registry::register_global(Arc::new(self));
+        }
+    }
+
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, a new [`Pooled`] value is created and returned.
+    ///
+    /// While any copies of the returned [`Pooled`] value are still allocated,
+    /// calling this function is guaranteed to return a copy of the same value.
+    ///
+    /// For the built-in pool types, this accepts:
+    ///
+    /// - [`StringPool`]: [`String`] or [`&str`](str), returning a
+    ///   [`GlobalString`].
+    /// - [`PathPool`]: [`PathBuf`] or [`&Path`](Path), returning a
+    ///   [`GlobalPath`].
+    /// - [`OsStringPool`]: [`OsString`] or [`&OsStr`](OsStr), returning a
+    ///   [`GlobalOsString`].
+    /// - [`CStringPool`]: [`CString`] or [`&CStr`](CStr), returning a
+    ///   [`GlobalCString`].
+    /// - [`BufferPool`]: [`Vec<u8>`] or `&[u8]`, returning a
+    ///   [`GlobalBuffer`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if inserting a new value would exceed this pool's
+    /// [limits](PoolLimits). [`GlobalPool::get_checked()`] returns an error
+    /// instead.
+    pub fn get<'a, V>(&'static self, value: V) -> Pooled<&'static Self, S>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
     {
         let value = value.into();
         self.with_active_symbols(|symbols| symbols.get(value, &self))
     }
 
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, a new [`Pooled`] value is created and returned.
+    ///
+    /// This function behaves like [`GlobalPool::get()`], except that it returns an
+    /// error instead of panicking. Like `get()`, it waits for the pool's lock
+    /// if another thread holds it.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OutOfMemory`]: The pool could not grow its tables to hold
+    ///   `value`.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting `value` would exceed this pool's
+    ///   [limits](PoolLimits).
+    pub fn get_checked<'a, V>(&'static self, value: V) -> Result<Pooled<&'static Self, S>, Error>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        let value = value.into();
+        self.with_active_symbols(|symbols| symbols.try_get(value, &self))
+    }
+
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, a new [`Pooled`] value is created and returned.
+    ///
+    /// This function behaves like [`GlobalPool::get_checked()`], except that it
+    /// never waits for the pool's lock.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::WouldBlock`]: The pool is currently locked.
+    /// - [`Error::OutOfMemory`]: The pool could not grow its tables to hold
+    ///   `value`.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting `value` would exceed this pool's
+    ///   [limits](PoolLimits).
+    pub fn try_get<'a, V>(&'static self, value: V) -> Result<Pooled<&'static Self, S>, Error>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        let value = value.into();
+        self.try_with_active_symbols(|symbols| symbols.try_get(value, &self))
+            .ok_or(Error::WouldBlock)?
+    }
+
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, a new [`Pooled`] value is created and returned.
+    ///
+    /// This function behaves like [`GlobalPool::get()`], except that each
+    /// thread keeps a small cache of values it has recently retrieved using
+    /// this function. When `value` is found in the current thread's cache, the
+    /// pool is not locked.
+    ///
+    /// Each thread caches up to 64 values per pool. Cached values are kept
+    /// allocated in the pool until they are evicted from the cache, the cache
+    /// is cleared using [`GlobalPool::clear_thread_cache()`], or the thread
+    /// exits.
+    ///
+    /// ```rust
+    /// use interner::global::{GlobalString, StringPool};
+    ///
+    /// static STRINGS: StringPool = StringPool::new();
+    ///
+    /// let first = STRINGS.get_cached("hello");
+    /// // This lookup is served from this thread's cache.
+    /// let second = STRINGS.get_cached("hello");
+    /// assert!(GlobalString::ptr_eq(&first, &second));
+    /// ```
+    pub fn get_cached<'a, V>(&'static self, value: V) -> Pooled<&'static Self, S>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        cache::get(self, value.into())
+    }
+
+    /// Releases all values cached by the current thread for this pool by
+    /// [`GlobalPool::get_cached()`].
+    pub fn clear_thread_cache(&'static self) {
+        cache::clear(self);
+    }
+
+    /// Records a lookup served by [`GlobalPool::get_cached()`] from the current
+    /// thread's cache.
+    #[cfg(feature = "stats")]
+    pub(crate) fn record_cached_hit(&self) {
+        self.cached_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Invokes `visitor` with a reference to each value currently stored in
+    /// this pool.
+    ///
+    /// Unlike [`GlobalPool::pooled()`], no [`Pooled`] handles are created. The
+    /// pool is locked while `visitor` is invoked, so `visitor` must not access
+    /// this pool. Dropping [`Pooled`] values from this pool within `visitor` is
+    /// allowed.
+    pub fn for_each<F>(&'static self, mut visitor: F)
+    where
+        F: FnMut(&T::Boxed),
+    {
+        self.with_active_symbols(|symbols| symbols.values().for_each(&mut visitor));
+    }
+
+    /// Folds every value currently stored in this pool into an accumulator
+    /// using `visitor`, returning the final accumulator.
+    ///
+    /// Unlike [`GlobalPool::pooled()`], no [`Pooled`] handles are created. The
+    /// pool is locked while `visitor` is invoked, so `visitor` must not access
+    /// this pool. Dropping [`Pooled`] values from this pool within `visitor` is
+    /// allowed.
+    pub fn fold<B, F>(&'static self, init: B, visitor: F) -> B
+    where
+        F: FnMut(B, &T::Boxed) -> B,
+    {
+        self.with_active_symbols(|symbols| symbols.values().fold(init, visitor))
+    }
+
+    /// Locks this pool and returns a snapshot that borrows each value
+    /// currently stored in it.
+    ///
+    /// Unlike [`GlobalPool::pooled()`], no [`Pooled`] handles are created. The
+    /// pool stays locked until the snapshot is dropped, so this pool must not
+    /// be accessed from the current thread while the snapshot is alive.
+    /// Dropping [`Pooled`] values from this pool while the snapshot is alive is
+    /// allowed.
+    ///
+    /// ```rust
+    /// use interner::global::StringPool;
+    ///
+    /// static POOL: StringPool = StringPool::new();
+    ///
+    /// let hello = POOL.get("hello");
+    /// let total: usize = POOL.iter_snapshot().iter().map(|value| value.len()).sum();
+    /// assert_eq!(total, 5);
+    /// ```
+    #[must_use]
+    pub fn iter_snapshot(&'static self) -> PoolSnapshot<'static, &'static Self, S> {
+        let mut state = pool::lock(&self, &self.state);
+        state.initialized(self);
+        PoolSnapshot::new(self, state)
+    }
+
+    /// Returns an immutable snapshot of this pool's current contents, which
+    /// can be read without locking.
+    ///
+    /// The returned [`FrozenPool`] keeps all values that are currently in
+    /// this pool allocated until it is dropped.
+    #[must_use]
+    pub fn freeze(&'static self) -> FrozenPool<&'static Self, S>
+    where
+        S: Clone,
+    {
+        self.with_active_symbols(|symbols| FrozenPool::new(self, symbols))
+    }
+
+    /// Returns the limits that are enforced when inserting new values into
+    /// this pool.
+    #[must_use]
+    pub fn limits(&self) -> PoolLimits {
+        self.limits.get()
+    }
+
+    /// Sets the limits that are enforced when inserting new values into this
+    /// pool. Fallible functions such as [`GlobalPool::get_checked()`] return an
+    /// error when a limit would be exceeded, while functions such as
+    /// [`GlobalPool::get()`] panic.
+    ///
+    /// Values already in the pool are not affected when limits are lowered.
+    pub fn set_limits(&self, limits: PoolLimits) {
+        self.limits.set(limits);
+    }
+
+    /// Registers `observer` to be invoked each time a value is inserted into
+    /// or freed from this pool.
+    ///
+    /// Observers are invoked after the pool is unlocked, so they can use the
+    /// pool without deadlocking. See [`PoolEvent`] for more information.
+    pub fn add_observer<F>(&'static self, observer: F)
+    where
+        F: Fn(&PoolEvent<'_, &'static Self, S>) + Send + Sync + 'static,
+    {
+        self.observers.add(Arc::new(observer));
+    }
+
+    /// Removes all observers registered with [`GlobalPool::add_observer()`].
+    pub fn clear_observers(&self) {
+        self.observers.clear();
+    }
+
+    /// Returns the [`ReleaseMode`] this pool uses when values are no longer
+    /// referenced.
+    #[must_use]
+    pub fn release_mode(&self) -> ReleaseMode {
+        self.releases.mode()
+    }
+
+    /// Sets the [`ReleaseMode`] this pool uses when values are no longer
+    /// referenced.
+    ///
+    /// Values that are queued when switching to [`ReleaseMode::Immediate`]
+    /// are removed the next time the pool retrieves a value or is collected.
+    pub fn set_release_mode(&self, mode: ReleaseMode) {
+        self.releases.set_mode(mode);
+    }
+
+    /// Removes all values whose release was deferred and that are still
+    /// unreferenced, returning the number of values removed.
+    ///
+    /// This only has an effect when using [`ReleaseMode::Deferred`].
+    #[allow(clippy::must_use_candidate)] // The number of values removed is informational.
+    pub fn collect(&'static self) -> usize {
+        self.with_active_symbols(|symbols| symbols.collect(&self.releases))
+    }
+
+    /// Returns the number of values currently stored in this pool.
+    #[must_use]
+    pub fn len(&'static self) -> usize {
+        self.with_active_symbols(|symbols| symbols.len())
+    }
+
+    /// Returns true if this pool currently contains no values.
+    #[must_use]
+    pub fn is_empty(&'static self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an estimate of the memory used by this pool, and the memory
+    /// saved by sharing its values.
+    #[must_use]
+    pub fn memory_usage(&'static self) -> MemoryUsage {
+        self.with_active_symbols(|symbols| symbols.memory_usage())
+    }
+
+    /// Returns statistics about how this pool has been used since it was
+    /// created.
+    #[cfg(feature = "stats")]
+    #[must_use]
+    pub fn stats(&'static self) -> PoolStats {
+        self.with_active_symbols(|symbols| symbols.stats)
+            .with_cached_hits(self.cached_hits.load(Ordering::Relaxed))
+    }
+
+    /// Returns the number of values this pool can hold without reallocating.
+    #[must_use]
+    pub fn capacity(&'static self) -> usize {
+        self.with_active_symbols(|symbols| symbols.capacity())
+    }
+
+    /// Reserves capacity for at least `additional` more values to be inserted
+    /// without reallocating.
+    pub fn reserve(&'static self, additional: usize) {
+        self.with_active_symbols(|symbols| symbols.reserve(additional));
+    }
+
+    /// Shrinks the capacity of this pool as much as possible.
+    ///
+    /// Unused slots at the end of the pool's storage are released. Values
+    /// that are still pooled are never moved.
+    pub fn shrink_to_fit(&'static self) {
+        self.with_active_symbols(Pool::shrink_to_fit);
+    }
+
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, `None` is returned.
+    ///
+    /// Unlike [`GlobalPool::get()`], this function never inserts `value` into the
+    /// pool. This allows checking untrusted input against the values already
+    /// in the pool without allowing the pool to grow.
+    #[must_use]
+    pub fn get_existing(&'static self, value: &T::Borrowed) -> Option<Pooled<&'static Self, S>> {
+        self.with_active_symbols(|symbols| symbols.get_existing(value))
+    }
+
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, `None` is returned.
+    ///
+    /// This function behaves like [`GlobalPool::get_existing()`], except that it
+    /// never waits for the pool's lock.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::WouldBlock`] if the pool is currently locked.
+    pub fn try_get_existing(
+        &'static self,
+        value: &T::Borrowed,
+    ) -> Result<Option<Pooled<&'static Self, S>>, Error> {
+        self.try_with_active_symbols(|symbols| symbols.get_existing(value))
+            .ok_or(Error::WouldBlock)
+    }
+
+    /// Returns true if `value` is currently stored in this pool.
+    #[must_use]
+    pub fn contains(&'static self, value: &T::Borrowed) -> bool {
+        self.with_active_symbols(|symbols| symbols.contains(value))
+    }
+
+    /// Returns the backtraces captured when each live [`Pooled`] copy of
+    /// `value` was created or cloned, in the order they were captured.
+    ///
+    /// The pool's own references to `value` are not included. If `value` is
+    /// not stored in this pool, an empty list is returned.
+    #[cfg(feature = "track-holders")]
+    #[must_use]
+    pub fn holders_of(&'static self, value: &T::Borrowed) -> Vec<Arc<Backtrace>> {
+        self.with_active_symbols(|symbols| symbols.holders_of(value))
+    }
+
+    /// Looks up `value` in this pool, returning an [`Entry`] that can be used
+    /// to decide whether to insert the value.
+    ///
+    /// The pool is not locked while the returned [`Entry`] is held. If another
+    /// thread inserts the same value before [`VacantEntry::insert()`] is
+    /// called, the existing value will be returned by `insert()`.
+    pub fn entry<'a, V>(&'static self, value: V) -> Entry<'a, &'static Self, S>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        let value = value.into();
+        match self.get_existing(&value) {
+            Some(pooled) => Entry::Occupied(pooled),
+            None => Entry::Vacant(VacantEntry::new(self, value)),
+        }
+    }
+
+    /// Returns the [`Pooled`] value that `symbol` refers to, or `None` if no
+    /// value is stored in the symbol's slot.
+    ///
+    /// If `symbol` was retrieved using [`Pooled::symbol()`] and all copies of
+    /// that value have since been dropped, this function may return `None` or
+    /// an unrelated value that has reused the same slot.
+    #[must_use]
+    pub fn resolve(&'static self, symbol: Symbol) -> Option<Pooled<&'static Self, S>> {
+        self.with_active_symbols(|symbols| symbols.resolve(symbol.index()))
+    }
+
+    /// Returns a copy of the [`Pooled`] value for `value`, inserting it into
+    /// the pool if needed, and marks it as permanent.
+    ///
+    /// Permanent values are never removed from the pool, even after all
+    /// copies of the returned [`Pooled`] value are dropped. The value will
+    /// stay allocated for the duration of the process.
+    ///
+    /// [`Pooled::is_permanent()`] can be used to check whether a value
+    /// returned from [`GlobalPool::pooled()`] is permanent.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if inserting a new value would exceed this pool's
+    /// [limits](PoolLimits). [`GlobalPool::try_make_permanent()`] returns an
+    /// error instead.
+    pub fn make_permanent<'a, V>(&'static self, value: V) -> Pooled<&'static Self, S>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        match self.try_make_permanent(value) {
+            Ok(pooled) => pooled,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Returns a copy of the [`Pooled`] value for `value`, inserting it into
+    /// the pool if needed, and marks it as permanent.
+    ///
+    /// This function behaves like [`GlobalPool::make_permanent()`], except that
+    /// it returns an error instead of panicking. It waits for the pool's lock
+    /// if another thread holds it.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OutOfMemory`]: The pool could not grow its tables to hold
+    ///   `value`.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting `value` would exceed this pool's
+    ///   [limits](PoolLimits).
+    pub fn try_make_permanent<'a, V>(
+        &'static self,
+        value: V,
+    ) -> Result<Pooled<&'static Self, S>, Error>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        let value = value.into();
+        self.with_active_symbols(|symbols| {
+            let pooled = symbols.try_get(value, &self)?;
+            symbols.make_permanent(&pooled);
+            Ok(pooled)
+        })
+    }
+
+    /// Returns a [`Symbol`] for `value`, inserting it into the pool if needed.
+    ///
+    /// The value is made permanent using [`GlobalPool::make_permanent()`],
+    /// which ensures the returned symbol can always be resolved using
+    /// [`GlobalPool::resolve()`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if inserting a new value would exceed this pool's
+    /// [limits](PoolLimits), or if the value's index does not fit in a
+    /// [`Symbol`]. [`GlobalPool::try_symbol()`] returns an error instead.
+    #[must_use]
+    pub fn symbol<'a, V>(&'static self, value: V) -> Symbol
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        match self.try_symbol(value) {
+            Ok(symbol) => symbol,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Returns a [`Symbol`] for `value`, inserting it into the pool if needed.
+    ///
+    /// This function behaves like [`GlobalPool::symbol()`], except that it returns
+    /// an error instead of panicking. It waits for the pool's lock if another
+    /// thread holds it.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OutOfMemory`]: The pool could not grow its tables to hold
+    ///   `value`.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting `value` would exceed this pool's
+    ///   [limits](PoolLimits).
+    /// - [`Error::SymbolOverflow`]: The value's index does not fit in a
+    ///   [`Symbol`].
+    pub fn try_symbol<'a, V>(&'static self, value: V) -> Result<Symbol, Error>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        self.try_make_permanent(value)?.try_symbol()
+    }
+}
+
+impl<S> GlobalPool<String, S>
+where
+    S: BuildHasher,
+{
     /// Returns a static pooled string, which keeps the pooled string allocated
     /// for the duration of the process.
     ///
@@ -223,19 +1036,6 @@ impl<S> GlobalPool<PathBuf, S>
 where
     S: BuildHasher,
 {
-    /// Returns a copy of an existing [`GlobalPath`] if one is found.
-    /// Otherwise, a new [`GlobalPath`] is created and returned.
-    ///
-    /// While any copies of the returned [`GlobalPath`] are still allocated,
-    /// calling this function is guaranteed to return a copy of the same path.
-    pub fn get<'a, V>(&'static self, value: V) -> GlobalPath<S>
-    where
-        V: Into<Cow<'a, Path>>,
-    {
-        let value = value.into();
-        self.with_active_symbols(|symbols| symbols.get(value, &self))
-    }
-
     // This function serves no purpose, currently, as there's no way to get a
     // static path in a const context -- Path::new() isn't const.
     // /// Returns a static pooled path, which keeps the pooled path allocated for
@@ -257,24 +1057,125 @@ where
     }
 }
 
-impl<S> GlobalPool<Vec<u8>, S>
+impl<S> GlobalPool<OsString, S>
+where
+    S: BuildHasher,
+{
+    /// Returns a static pooled OS string, which keeps the pooled OS string
+    /// allocated for the duration of the process. The OS string is initialized
+    /// using the function provided when it is retrieved for the first time.
+    pub const fn get_static_with(
+        &'static self,
+        function: fn() -> Cow<'static, OsStr>,
+    ) -> StaticPooledOsString<S> {
+        StaticPooledOsString::new_fn(self, function)
+    }
+}
+
+impl<S> GlobalPool<CString, S>
 where
     S: BuildHasher,
 {
-    /// Returns a copy of an existing [`GlobalBuffer`] if one is found.
-    /// Otherwise, a new [`GlobalBuffer`] is created and returned.
+    /// Returns a copy of an existing [`GlobalCString`] containing `string` if
+    /// one is found. Otherwise, a new [`GlobalCString`] is created and
+    /// returned.
     ///
-    /// While any copies of the returned [`GlobalBuffer`] are still allocated,
-    /// calling this function is guaranteed to return a copy of the same byte
-    /// buffer.
-    pub fn get<'a, V>(&'static self, value: V) -> GlobalBuffer<S>
-    where
-        V: Into<Cow<'a, [u8]>>,
-    {
-        let value = value.into();
-        self.with_active_symbols(|symbols| symbols.get(value, &self))
+    /// This function can be used to convert a [`GlobalString`] into a
+    /// [`GlobalCString`]:
+    ///
+    /// ```rust
+    /// use interner::global::{CStringPool, StringPool};
+    ///
+    /// static STRINGS: StringPool = StringPool::new();
+    /// static C_STRINGS: CStringPool = CStringPool::new();
+    ///
+    /// let string = STRINGS.get("hello");
+    /// let c_string = C_STRINGS.get_from_str(&string).unwrap();
+    /// assert_eq!(c_string.to_str(), Ok("hello"));
+    ///
+    /// assert!(C_STRINGS.get_from_str("nul\0byte").is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NulError`] if `string` contains an interior NUL byte.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if inserting a new value would exceed this pool's
+    /// [limits](PoolLimits). [`GlobalPool::get_from_str_checked()`] returns an
+    /// error instead.
+    pub fn get_from_str(&'static self, string: &str) -> Result<GlobalCString<S>, NulError> {
+        let value = CString::new(string)?;
+        Ok(self.get(value))
+    }
+
+    /// Returns a copy of an existing [`GlobalCString`] containing `string` if
+    /// one is found. Otherwise, a new [`GlobalCString`] is created and
+    /// returned.
+    ///
+    /// This function behaves like [`GlobalPool::get_from_str()`], except that
+    /// it returns an error instead of panicking. It waits for the pool's lock
+    /// if another thread holds it.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InteriorNul`]: `string` contains an interior NUL byte.
+    /// - [`Error::OutOfMemory`]: The pool could not grow its tables to hold
+    ///   the value.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting the value would exceed this pool's
+    ///   [limits](PoolLimits).
+    pub fn get_from_str_checked(&'static self, string: &str) -> Result<GlobalCString<S>, Error> {
+        let value = CString::new(string)?;
+        self.get_checked(value)
+    }
+
+    /// Returns a copy of an existing [`GlobalCString`] containing `string` if
+    /// one is found. Otherwise, a new [`GlobalCString`] is created and
+    /// returned.
+    ///
+    /// This function behaves like [`GlobalPool::get_from_str()`], except that
+    /// it never waits for the pool's lock and never panics.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InteriorNul`]: `string` contains an interior NUL byte.
+    /// - [`Error::WouldBlock`]: The pool is currently locked.
+    /// - [`Error::OutOfMemory`]: The pool could not grow its tables to hold
+    ///   the value.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting the value would exceed this pool's
+    ///   [limits](PoolLimits).
+    pub fn try_get_from_str(&'static self, string: &str) -> Result<GlobalCString<S>, Error> {
+        let value = CString::new(string)?;
+        self.try_get(value)
     }
 
+    /// Returns a static pooled C string, which keeps the pooled C string
+    /// allocated for the duration of the process.
+    ///
+    /// The C string is not initialized until it is retrieved for the first
+    /// time.
+    pub const fn get_static(&'static self, value: &'static CStr) -> StaticPooledCString<S> {
+        StaticPooledCString::new(self, value)
+    }
+
+    /// Returns a static pooled C string, which keeps the pooled C string
+    /// allocated for the duration of the process. The C string is initialized
+    /// using the function provided when it is retrieved for the first time.
+    pub const fn get_static_with(
+        &'static self,
+        function: fn() -> Cow<'static, CStr>,
+    ) -> StaticPooledCString<S> {
+        StaticPooledCString::new_fn(self, function)
+    }
+}
+
+impl<S> GlobalPool<Vec<u8>, S>
+where
+    S: BuildHasher,
+{
     /// Returns a static pooled buffer, which keeps the pooled buffer allocated for
     /// the duration of the process.
     ///
@@ -327,13 +1228,48 @@ where
     cell: OnceLock<GlobalPath<S>>,
 }
 
+/// A lazily-initialized [`GlobalOsString`] that stays allocated for the
+/// duration of the process.
+#[derive(Debug)]
+pub struct StaticPooledOsString<S = RandomState>
+where
+    S: BuildHasher + 'static,
+{
+    init: StaticOsStringInit<S>,
+    cell: OnceLock<GlobalOsString<S>>,
+}
+
+/// A lazily-initialized [`GlobalCString`] that stays allocated for the
+/// duration of the process.
+#[derive(Debug)]
+pub struct StaticPooledCString<S = RandomState>
+where
+    S: BuildHasher + 'static,
+{
+    init: StaticCStringInit<S>,
+    cell: OnceLock<GlobalCString<S>>,
+}
+
+/// Records that `pooled` is held by a `StaticPooled*` value, which excludes it
+/// from [`GlobalPool::leak_report()`].
+fn held_statically<T, S>(
+    pooled: Pooled<&'static GlobalPool<T, S>, S>,
+) -> Pooled<&'static GlobalPool<T, S>, S>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher,
+{
+    pooled.0 .0.statics.fetch_add(1, Ordering::Relaxed);
+    pooled
+}
+
 macro_rules! impl_static_pooled {
     ($name:ident, $pooled:ident, $statename:ident, $owned:ty, $borrowed:ty) => {
         impl<S> $name<S>
         where
             S: BuildHasher + 'static,
         {
-            #[allow(dead_code)] // This function isn't called for StaticPooledPath, because there's no way to get a static Path.
+            #[allow(dead_code)] // This function isn't called for StaticPooledPath or StaticPooledOsString, because there's no way to get a static Path or OsStr.
             const fn new(pool: &'static GlobalPool<$owned, S>, value: &'static $borrowed) -> Self {
                 Self {
                     init: $statename::Static(pool, value),
@@ -360,15 +1296,82 @@ macro_rules! impl_static_pooled {
             ///
             /// All subsequent accesses will be non-blocking.
             pub fn get(&self) -> &$pooled<S> {
-                self.cell.get_or_init(|| match self.init {
-                    $statename::Static(pool, value) => pool.get(value).clone(),
-                    $statename::Fn(pool, init) => pool.get(init()).clone(),
+                self.cell.get_or_init(|| {
+                    held_statically(match self.init {
+                        $statename::Static(pool, value) => pool.get(value).clone(),
+                        $statename::Fn(pool, init) => pool.get(init()).clone(),
+                    })
                 })
             }
+
+            /// Returns a reference-counted clone of the contained resource.
+            ///
+            /// This function behaves like `get()`, except that initializing the
+            /// value never waits for the pool's lock and never panics. If an
+            /// error is returned, initialization is attempted again on the
+            /// next access.
+            ///
+            /// # Errors
+            ///
+            /// - [`Error::WouldBlock`]: The pool is currently locked.
+            /// - [`Error::OutOfMemory`]: The pool could not grow its tables
+            ///   to hold the value.
+            /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+            ///   [`Error::ValueTooLong`]: Inserting the value would exceed
+            ///   the pool's [limits](PoolLimits).
+            pub fn try_get(&self) -> Result<&$pooled<S>, Error> {
+                if let Some(pooled) = self.cell.get() {
+                    return Ok(pooled);
+                }
+
+                let pooled = match self.init {
+                    $statename::Static(pool, value) => pool.try_get(value)?,
+                    $statename::Fn(pool, init) => pool.try_get(init())?,
+                };
+                Ok(self.cell.get_or_init(|| held_statically(pooled)))
+            }
+
+            /// Returns a reference-counted clone of the contained resource.
+            ///
+            /// This function behaves like `get()`, except that initializing the
+            /// value returns an error instead of panicking. Like `get()`, it
+            /// waits for the pool's lock if another thread holds it. If an
+            /// error is returned, initialization is attempted again on the
+            /// next access.
+            ///
+            /// # Errors
+            ///
+            /// - [`Error::OutOfMemory`]: The pool could not grow its tables
+            ///   to hold the value.
+            /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+            ///   [`Error::ValueTooLong`]: Inserting the value would exceed
+            ///   the pool's [limits](PoolLimits).
+            pub fn get_checked(&self) -> Result<&$pooled<S>, Error> {
+                if let Some(pooled) = self.cell.get() {
+                    return Ok(pooled);
+                }
+
+                let pooled = match self.init {
+                    $statename::Static(pool, value) => pool.get_checked(value)?,
+                    $statename::Fn(pool, init) => pool.get_checked(init())?,
+                };
+                Ok(self.cell.get_or_init(|| held_statically(pooled)))
+            }
+        }
+
+        impl<S> Drop for $name<S>
+        where
+            S: BuildHasher + 'static,
+        {
+            fn drop(&mut self) {
+                if let Some(pooled) = self.cell.get() {
+                    pooled.0 .0.statics.fetch_sub(1, Ordering::Relaxed);
+                }
+            }
         }
 
         #[derive(Debug, Clone, Copy)]
-        #[allow(dead_code)] // Path can't use the Static variant.
+        #[allow(dead_code)] // Path and OsStr can't use the Static variant.
         enum $statename<S>
         where
             S: BuildHasher + 'static,
@@ -428,3 +1431,104 @@ impl_static_pooled!(
     [u8]
 );
 impl_static_pooled!(StaticPooledPath, GlobalPath, StaticPathInit, PathBuf, Path);
+impl_static_pooled!(
+    StaticPooledOsString,
+    GlobalOsString,
+    StaticOsStringInit,
+    OsString,
+    OsStr
+);
+impl_static_pooled!(
+    StaticPooledCString,
+    GlobalCString,
+    StaticCStringInit,
+    CString,
+    CStr
+);
+
+/// The values remaining in a [`GlobalPool`], returned by
+/// [`GlobalPool::leak_report()`].
+#[derive(Debug, Clone)]
+pub struct LeakReport<T>
+where
+    T: Poolable,
+{
+    name: Option<&'static str>,
+    entries: Vec<LeakedValue<T>>,
+}
+
+impl<T> LeakReport<T>
+where
+    T: Poolable,
+{
+    /// Returns the values that are still referenced, ordered by their index in
+    /// the pool.
+    #[must_use]
+    pub fn entries(&self) -> &[LeakedValue<T>] {
+        &self.entries
+    }
+
+    /// Returns true if no values are still referenced.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns this report without the entries for `expected`, which are
+    /// values that are allowed to remain in the pool.
+    #[must_use]
+    pub fn excluding(mut self, expected: &[&T::Borrowed]) -> Self {
+        self.entries.retain(|entry| {
+            let value = Borrow::<T::Borrowed>::borrow(&entry.value);
+            !expected.contains(&value)
+        });
+        self
+    }
+
+    /// Asserts that no values are still referenced.
+    ///
+    /// # Panics
+    ///
+    /// This function panics with a description of each remaining value if
+    /// this report is not empty.
+    #[track_caller]
+    pub fn assert_empty(&self) {
+        assert!(self.is_empty(), "{self}");
+    }
+}
+
+impl<T> Display for LeakReport<T>
+where
+    T: Poolable,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} values remain in ", self.entries.len())?;
+        match self.name {
+            Some(name) => write!(f, "pool {name:?}")?,
+            None => f.write_str("the pool")?,
+        }
+        for entry in &self.entries {
+            write!(
+                f,
+                "\n  {:?} (index {}, {} references)",
+                entry.value, entry.index, entry.strong_count
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A value listed in a [`LeakReport`].
+#[derive(Debug, Clone)]
+pub struct LeakedValue<T>
+where
+    T: Poolable,
+{
+    /// The value that is still referenced.
+    pub value: T::Boxed,
+    /// The value's index in the pool.
+    pub index: usize,
+    /// The number of references to the value, excluding the pool's own
+    /// references and references held by `StaticPooled*` values.
+    pub strong_count: usize,
+}
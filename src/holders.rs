@@ -0,0 +1,83 @@
+#[cfg(feature = "track-holders")]
+use std::backtrace::Backtrace;
+#[cfg(feature = "track-holders")]
+use std::collections::BTreeMap;
+#[cfg(feature = "track-holders")]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "track-holders")]
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// The backtraces of the live handles to a single pooled value.
+#[cfg(feature = "track-holders")]
+#[derive(Default)]
+pub struct Holders {
+    next_id: AtomicU64,
+    live: Mutex<BTreeMap<u64, Arc<Backtrace>>>,
+}
+
+#[cfg(feature = "track-holders")]
+impl Holders {
+    /// Returns the backtraces of the live handles, in the order the handles
+    /// were created.
+    pub fn backtraces(&self) -> Vec<Arc<Backtrace>> {
+        self.live
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(feature = "track-holders")]
+impl std::fmt::Debug for Holders {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let live = self.live.lock().unwrap_or_else(PoisonError::into_inner);
+        f.debug_struct("Holders")
+            .field("len", &live.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The record of a single [`Pooled`](crate::Pooled) handle, which is removed
+/// from its value's [`Holders`] when the handle is dropped.
+///
+/// Without the `track-holders` feature, this type is empty.
+#[derive(Debug)]
+pub struct Holder(#[cfg(feature = "track-holders")] Option<(Arc<Holders>, u64)>);
+
+impl Holder {
+    /// Records a backtrace for a new handle to a value with `holders`.
+    #[cfg(feature = "track-holders")]
+    pub fn track(holders: &Arc<Holders>) -> Self {
+        let id = holders.next_id.fetch_add(1, Ordering::Relaxed);
+        holders
+            .live
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(id, Arc::new(Backtrace::force_capture()));
+        Self(Some((holders.clone(), id)))
+    }
+
+    /// Returns a holder for a handle owned by the pool itself, which is never
+    /// reported.
+    pub const fn untracked() -> Self {
+        Self(
+            #[cfg(feature = "track-holders")]
+            None,
+        )
+    }
+}
+
+#[cfg(feature = "track-holders")]
+impl Drop for Holder {
+    fn drop(&mut self) {
+        if let Some((holders, id)) = self.0.take() {
+            holders
+                .live
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove(&id);
+        }
+    }
+}
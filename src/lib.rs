@@ -14,29 +14,55 @@
     clippy::module_name_repetitions
 )]
 
+use std::borrow::Cow;
+use std::ffi::{CStr, OsStr};
 use std::fmt::{Debug, Display};
 use std::hash::{BuildHasher, Hash};
 use std::ops::Deref;
 use std::path::Path;
+use std::sync::{atomic, Arc, Weak};
 
+mod cache;
+mod error;
+mod frozen;
 /// Global interning pools.
 pub mod global;
+mod holders;
 mod pool;
+/// A process-wide registry of pools.
+#[cfg(feature = "registry")]
+pub mod registry;
+/// Interning pools that are split into multiple independently locked shards.
+pub mod sharded;
 /// Shared interning pools that have no global state.
 pub mod shared;
 #[cfg(test)]
 mod tests;
 
-use crate::pool::{PoolKindSealed, SharedData};
+pub use crate::error::Error;
+pub use crate::frozen::FrozenPool;
+use crate::holders::Holder;
+#[cfg(feature = "tracing")]
+pub use crate::pool::set_lock_wait_threshold;
+#[cfg(feature = "stats")]
+pub use crate::pool::PoolStats;
+use crate::pool::{Data, PoolKindSealed, SharedData};
+pub use crate::pool::{
+    MemoryUsage, PoolEvent, PoolEventKind, PoolLimits, PoolSnapshot, Poolable, ReleaseMode,
+    SnapshotIter,
+};
 
 /// A kind of interning pool. Currently there are only two types of pools:
 ///
 /// - Global, used through the [`global::StringPool`],
-///   [`GlobalPath`](global::GlobalPath), and
+///   [`GlobalPath`](global::GlobalPath),
+///   [`GlobalOsString`](global::GlobalOsString),
+///   [`GlobalCString`](global::GlobalCString), and
 ///   [`GlobalBuffer`](global::GlobalBuffer) types.
 /// - Shared, used through the [`StringPool`](shared::StringPool),
-///   [`PathPool`](shared::PathPool), and [`BufferPool`](shared::BufferPool)
-///   types.
+///   [`PathPool`](shared::PathPool), [`OsStringPool`](shared::OsStringPool),
+///   [`CStringPool`](shared::CStringPool), and
+///   [`BufferPool`](shared::BufferPool) types.
 pub trait PoolKind<S>: Clone + PartialEq + PoolKindSealed<S> {}
 
 /// A type that ensures only one copy of each value exists in its pool, enabling
@@ -50,7 +76,11 @@ pub trait PoolKind<S>: Clone + PartialEq + PoolKindSealed<S> {}
 /// using incompatible [`Hash`] implementations to look up values in
 /// `HashMap`s/`HashSet`s where this type is
 /// used as the key.
-pub struct Pooled<P, S>(SharedData<P, S>)
+pub struct Pooled<P, S>(
+    SharedData<P, S>,
+    #[allow(dead_code)] // The holder is only used when it is dropped.
+    Holder,
+)
 where
     P: PoolKind<S>,
     S: BuildHasher;
@@ -60,6 +90,22 @@ where
     P: PoolKind<S>,
     S: BuildHasher,
 {
+    /// Returns a new handle to `data`. With the `track-holders` feature, a
+    /// backtrace is recorded for the handle until it is dropped.
+    pub(crate) fn new(data: SharedData<P, S>) -> Self {
+        #[cfg(feature = "track-holders")]
+        let holder = Holder::track(&data.0.holders);
+        #[cfg(not(feature = "track-holders"))]
+        let holder = Holder::untracked();
+        Self(data, holder)
+    }
+
+    /// Returns a handle to `data` that is owned by its pool, which is never
+    /// reported as a holder of the value.
+    pub(crate) fn held_by_pool(data: SharedData<P, S>) -> Self {
+        Self(data, Holder::untracked())
+    }
+
     /// Returns true if `this` and `other` point to the exact same instance of
     /// the value. Returns false if `this` and `other` are from different pools
     /// or if the index within the pool does not match.
@@ -74,9 +120,129 @@ where
     {
         this.0 .0.pool == other.0 .0.pool && this.0 .0.index == other.0 .0.index
     }
+
+    /// Returns true if this value has been made permanent, either through
+    /// [`Pooled::leak()`] or the pool's `make_permanent()` or `symbol()`
+    /// functions. Permanent values are never removed from their pool.
+    #[must_use]
+    pub fn is_permanent(this: &Self) -> bool {
+        this.0 .0.permanent.load(atomic::Ordering::Relaxed)
+    }
+
+    /// Marks this value as permanent and returns a reference to the pooled
+    /// value that is valid for the duration of the process.
+    ///
+    /// This is useful for values that are discovered at runtime but should
+    /// never be released, such as configuration keys loaded at startup.
+    ///
+    /// ```rust
+    /// use interner::global::StringPool;
+    ///
+    /// static STRINGS: StringPool = StringPool::new();
+    ///
+    /// let key: &'static str = STRINGS.get(String::from("config-key")).leak();
+    /// assert_eq!(key, "config-key");
+    /// ```
+    #[must_use]
+    pub fn leak(self) -> &'static P::Pooled
+    where
+        P: 'static,
+        S: 'static,
+    {
+        self.0
+             .0
+            .pool
+            .with_active_symbols(|symbols| symbols.make_permanent(&self));
+        let leaked: &'static Self = Box::leak(Box::new(self));
+        &leaked.0 .0.value
+    }
+
+    /// Creates a new [`WeakPooled`] pointer to this value.
+    ///
+    /// Weak pointers do not keep the value allocated in its pool.
+    #[must_use]
+    pub fn downgrade(this: &Self) -> WeakPooled<P, S> {
+        WeakPooled(Arc::downgrade(&this.0 .0))
+    }
+
+    /// Returns the [`Symbol`] that refers to this value in its pool.
+    ///
+    /// The returned symbol can be resolved back into a [`Pooled`] value by
+    /// passing it to the pool's `resolve()` function. The symbol only refers
+    /// to this value while this value remains allocated in its pool: once all
+    /// copies of this value are dropped, the pool may reuse the symbol for
+    /// another value. To obtain a symbol that never becomes invalid, use the
+    /// pool's `symbol()` function instead.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the value's index in the pool does not fit in a
+    /// `u32`. [`Pooled::try_symbol()`] returns an error instead.
+    #[must_use]
+    pub fn symbol(&self) -> Symbol {
+        match self.try_symbol() {
+            Ok(symbol) => symbol,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Returns a [`Symbol`] that refers to this value, or
+    /// [`Error::SymbolOverflow`] if the value's index in the pool does not fit
+    /// in a `u32`.
+    ///
+    /// See [`Pooled::symbol()`] for more information.
+    pub fn try_symbol(&self) -> Result<Symbol, Error> {
+        self.0
+             .0
+            .pool
+            .symbol_index(self.0 .0.index)
+            .and_then(|index| u32::try_from(index).ok())
+            .map(Symbol)
+            .ok_or(Error::SymbolOverflow)
+    }
 }
 
-impl<P, S> Clone for Pooled<P, S>
+/// A weak reference to a [`Pooled`] value, created by [`Pooled::downgrade()`].
+///
+/// A [`WeakPooled`] does not keep its value allocated in the pool. Once all
+/// [`Pooled`] copies of the value are dropped, the value is removed from the
+/// pool and [`WeakPooled::upgrade()`] returns `None`.
+///
+/// ```rust
+/// use interner::shared::StringPool;
+/// use interner::Pooled;
+///
+/// let pool = StringPool::default();
+/// let hello = pool.get("hello");
+/// let weak = Pooled::downgrade(&hello);
+/// assert_eq!(weak.upgrade().unwrap(), "hello");
+///
+/// drop(hello);
+/// assert!(weak.upgrade().is_none());
+/// ```
+pub struct WeakPooled<P, S>(Weak<Data<P, S>>)
+where
+    P: PoolKind<S>,
+    S: BuildHasher;
+
+impl<P, S> WeakPooled<P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    /// Attempts to upgrade this weak reference into a [`Pooled`] value.
+    /// Returns `None` if the value is no longer stored in its pool.
+    ///
+    /// This function briefly locks the value's pool.
+    #[must_use]
+    pub fn upgrade(&self) -> Option<Pooled<P, S>> {
+        let data = self.0.upgrade()?;
+        let pool = data.pool.clone_for_value();
+        pool.with_active_symbols(|symbols| symbols.upgrade(data))
+    }
+}
+
+impl<P, S> Clone for WeakPooled<P, S>
 where
     P: PoolKind<S>,
     S: BuildHasher,
@@ -86,6 +252,187 @@ where
     }
 }
 
+impl<P, S> Debug for WeakPooled<P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(WeakPooled)")
+    }
+}
+
+/// The result of looking up a value using a pool's `entry()` function.
+#[derive(Debug)]
+pub enum Entry<'a, P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    /// The value is already stored in the pool.
+    Occupied(Pooled<P, S>),
+    /// The value is not stored in the pool.
+    Vacant(VacantEntry<'a, P, S>),
+}
+
+impl<P, S> Entry<'_, P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    /// Returns the existing [`Pooled`] value, or inserts the value into the
+    /// pool if it is vacant.
+    #[must_use]
+    pub fn or_insert(self) -> Pooled<P, S> {
+        match self {
+            Entry::Occupied(pooled) => pooled,
+            Entry::Vacant(vacant) => vacant.insert(),
+        }
+    }
+}
+
+/// A value that was not found in its pool when looked up using the pool's
+/// `entry()` function.
+///
+/// ```rust
+/// use interner::shared::StringPool;
+/// use interner::Entry;
+///
+/// let pool = StringPool::default();
+/// let interned = match pool.entry("hello") {
+///     Entry::Occupied(existing) => existing,
+///     Entry::Vacant(vacant) => {
+///         assert_eq!(vacant.value(), "hello");
+///         vacant.insert()
+///     }
+/// };
+/// assert!(pool.contains("hello"));
+/// assert_eq!(interned, "hello");
+/// ```
+pub struct VacantEntry<'a, P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    pool: P,
+    value: Cow<'a, <P::Owned as Poolable>::Borrowed>,
+}
+
+impl<'a, P, S> VacantEntry<'a, P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    pub(crate) fn new(pool: P, value: Cow<'a, <P::Owned as Poolable>::Borrowed>) -> Self {
+        Self { pool, value }
+    }
+
+    /// Returns the value that was looked up.
+    #[must_use]
+    pub fn value(&self) -> &<P::Owned as Poolable>::Borrowed {
+        &self.value
+    }
+
+    /// Inserts the value into the pool, returning the new [`Pooled`] value.
+    ///
+    /// If the value was inserted by another thread after this entry was
+    /// created, the existing value is returned.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if inserting the value would exceed the pool's
+    /// [limits](PoolLimits). [`VacantEntry::try_insert()`] returns an error
+    /// instead.
+    #[must_use]
+    pub fn insert(self) -> Pooled<P, S> {
+        match self.try_insert() {
+            Ok(pooled) => pooled,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Inserts the value into the pool, returning the new [`Pooled`] value.
+    ///
+    /// This function behaves like [`VacantEntry::insert()`], except that it
+    /// returns an error instead of panicking. It waits for the pool's lock if
+    /// another thread holds it.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OutOfMemory`]: The pool could not grow its tables to hold
+    ///   the value.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting the value would exceed the pool's
+    ///   [limits](PoolLimits).
+    pub fn try_insert(self) -> Result<Pooled<P, S>, Error> {
+        let Self { pool, value } = self;
+        pool.with_active_symbols(|symbols| symbols.try_get(value, &pool))
+    }
+}
+
+impl<P, S> Debug for VacantEntry<'_, P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("VacantEntry").field(&self.value()).finish()
+    }
+}
+
+/// A compact, [`Copy`]-able handle to a value in a pool.
+///
+/// A [`Symbol`] is an index into a pool's storage and is four bytes in size.
+/// Unlike [`Pooled`], copying a symbol does not require any synchronization,
+/// which makes symbols well-suited for storing in large structures such as
+/// syntax trees or token streams.
+///
+/// Symbols do not keep their values alive. A symbol can be retrieved in two
+/// ways:
+///
+/// - From the pool's `symbol()` function, which makes the value permanent. The
+///   returned symbol will always resolve to the same value.
+/// - From [`Pooled::symbol()`], which is only valid while a copy of the
+///   [`Pooled`] value is retained.
+///
+/// Symbols do not record which pool they belong to. Resolving a symbol using a
+/// different pool than the one it was created from will return an unrelated
+/// value. Symbols of [sharded](crate::sharded) pools also record the shard
+/// their value is stored in.
+///
+/// ```rust
+/// use interner::shared::StringPool;
+///
+/// let pool = StringPool::default();
+/// let symbol = pool.symbol("hello");
+/// let copy = symbol;
+///
+/// assert_eq!(pool.resolve(copy).unwrap(), "hello");
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Returns the index of this symbol within its pool.
+    ///
+    /// For sharded pools, the index combines the shard and the index within
+    /// that shard.
+    #[must_use]
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl<P, S> Clone for Pooled<P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    fn clone(&self) -> Self {
+        Self::new(self.0.clone())
+    }
+}
+
 impl<P, S> Hash for Pooled<P, S>
 where
     P: PoolKind<S>,
@@ -214,6 +561,46 @@ where
     }
 }
 
+impl<P, S> PartialEq<OsStr> for Pooled<P, S>
+where
+    P: PoolKind<S, Pooled = Box<OsStr>>,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &OsStr) -> bool {
+        &***self == other
+    }
+}
+
+impl<'a, P, S> PartialEq<&'a OsStr> for Pooled<P, S>
+where
+    P: PoolKind<S, Pooled = Box<OsStr>>,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &&'a OsStr) -> bool {
+        self == *other
+    }
+}
+
+impl<P, S> PartialEq<CStr> for Pooled<P, S>
+where
+    P: PoolKind<S, Pooled = Box<CStr>>,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &CStr) -> bool {
+        ***self == *other
+    }
+}
+
+impl<'a, P, S> PartialEq<&'a CStr> for Pooled<P, S>
+where
+    P: PoolKind<S, Pooled = Box<CStr>>,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &&'a CStr) -> bool {
+        self == *other
+    }
+}
+
 impl<P, S> Ord for Pooled<P, S>
 where
     P: PoolKind<S>,
@@ -274,6 +661,46 @@ where
     }
 }
 
+impl<P, S> PartialOrd<OsStr> for Pooled<P, S>
+where
+    P: PoolKind<S, Pooled = Box<OsStr>>,
+    S: BuildHasher,
+{
+    fn partial_cmp(&self, other: &OsStr) -> Option<std::cmp::Ordering> {
+        (**self).as_ref().partial_cmp(other)
+    }
+}
+
+impl<'a, P, S> PartialOrd<&'a OsStr> for Pooled<P, S>
+where
+    P: PoolKind<S, Pooled = Box<OsStr>>,
+    S: BuildHasher,
+{
+    fn partial_cmp(&self, other: &&'a OsStr) -> Option<std::cmp::Ordering> {
+        self.partial_cmp(*other)
+    }
+}
+
+impl<P, S> PartialOrd<CStr> for Pooled<P, S>
+where
+    P: PoolKind<S, Pooled = Box<CStr>>,
+    S: BuildHasher,
+{
+    fn partial_cmp(&self, other: &CStr) -> Option<std::cmp::Ordering> {
+        (**self).as_ref().partial_cmp(other)
+    }
+}
+
+impl<'a, P, S> PartialOrd<&'a CStr> for Pooled<P, S>
+where
+    P: PoolKind<S, Pooled = Box<CStr>>,
+    S: BuildHasher,
+{
+    fn partial_cmp(&self, other: &&'a CStr) -> Option<std::cmp::Ordering> {
+        self.partial_cmp(*other)
+    }
+}
+
 impl<P, S> PartialOrd<[u8]> for Pooled<P, S>
 where
     P: PoolKind<S, Pooled = Box<[u8]>>,
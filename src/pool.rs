@@ -1,30 +1,146 @@
+#[cfg(feature = "track-holders")]
+use std::backtrace::Backtrace;
 use std::borrow::{Borrow, Cow};
+use std::cell::RefCell;
 use std::collections::hash_map::RandomState;
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
+use std::ffi::{CStr, CString, OsStr, OsString};
 use std::fmt::Debug;
 use std::hash::{BuildHasher, Hash};
 use std::marker::PhantomData;
+use std::mem;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::AtomicBool;
-use std::sync::{atomic, Arc};
+#[cfg(feature = "tracing")]
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::mpsc::{self, Receiver, Sender};
+#[cfg(feature = "tracing")]
+use std::sync::TryLockError;
+use std::sync::{atomic, Arc, Mutex, MutexGuard, OnceLock, PoisonError, RwLock, Weak};
+#[cfg(feature = "tracing")]
+use std::time::{Duration, Instant};
 
-use crate::{PoolKind, Pooled};
+#[cfg(feature = "track-holders")]
+use crate::holders::Holders;
+use crate::{Error, PoolKind, Pooled};
 
 pub trait PoolKindSealed<Hasher> {
     type Owned: Poolable<Boxed = Self::Pooled> + Debug + Clone + Eq + Hash + Ord;
-    type Pooled: Debug + Clone + Eq + Hash + Ord;
+    type Pooled: Borrow<<Self::Owned as Poolable>::Borrowed> + Debug + Clone + Eq + Hash + Ord;
 
     fn with_active_symbols<T>(&self, logic: impl FnOnce(&mut Pool<Self, Hasher>) -> T) -> T;
+    /// Invokes `logic` with the pool if it can be locked without blocking.
+    fn try_with_active_symbols<T>(
+        &self,
+        logic: impl FnOnce(&mut Pool<Self, Hasher>) -> T,
+    ) -> Option<T>;
+    /// Returns the limits of this pool, if this kind of pool supports limits.
+    fn enforced_limits(&self) -> Option<&AtomicLimits>;
+    /// Returns the queue of releases that have been deferred.
+    fn release_queue(&self) -> &ReleaseQueue<Self, Hasher>
+    where
+        Self: PoolKind<Hasher>,
+        Hasher: BuildHasher;
+    /// Returns the observers of this pool, if this kind of pool supports
+    /// observers.
+    fn observers(&self) -> Option<&Observers<Self, Hasher>>
+    where
+        Self: PoolKind<Hasher>,
+        Hasher: BuildHasher;
+    /// Returns the name this pool was given, if any.
+    fn pool_name(&self) -> Option<&'static str>;
+    /// Returns the values stored across all of this pool's shards, if this
+    /// pool is sharded.
+    fn shard_totals(&self) -> Option<&ShardTotals>;
+    /// Returns the index stored in a [`Symbol`](crate::Symbol) for the value in slot `index`
+    /// of this pool, or `None` if the index would overflow.
+    fn symbol_index(&self, index: usize) -> Option<usize> {
+        Some(index)
+    }
+    /// Returns a copy of this pool handle to be stored in one of the pool's
+    /// values.
+    fn clone_for_value(&self) -> Self;
     fn address_of(&self) -> *const ();
 }
 
+/// A type that can be stored in a [`SharedPool`](crate::shared::SharedPool)
+/// or [`GlobalPool`](crate::global::GlobalPool).
+///
+/// This trait is implemented for [`String`], [`PathBuf`], [`OsString`],
+/// [`CString`], and [`Vec<u8>`].
+/// Implementing it for other types allows them to be interned as well:
+///
+/// ```rust
+/// use std::borrow::Cow;
+///
+/// use interner::shared::SharedPool;
+/// use interner::{Poolable, Pooled};
+///
+/// #[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+/// struct QualifiedName {
+///     namespace: String,
+///     name: String,
+/// }
+///
+/// impl Poolable for QualifiedName {
+///     type Borrowed = Self;
+///     type Boxed = Self;
+///
+///     fn boxed(self) -> Self::Boxed {
+///         self
+///     }
+///
+///     // The default only counts the size of the struct itself, so the bytes
+///     // owned by each `String` are added.
+///     fn byte_len(value: &Self) -> usize {
+///         std::mem::size_of_val(value) + value.namespace.len() + value.name.len()
+///     }
+/// }
+///
+/// let pool = SharedPool::<QualifiedName>::default();
+/// let name = QualifiedName {
+///     namespace: String::from("std"),
+///     name: String::from("string"),
+/// };
+/// let first = pool.get(Cow::Borrowed(&name));
+/// let second = pool.get(Cow::Owned(name));
+/// assert!(Pooled::ptr_eq(&first, &second));
+/// ```
 pub trait Poolable {
-    type Boxed: Debug + Clone + Eq + Hash + Ord;
+    /// The borrowed form of this type, which is used to look up values in the
+    /// pool without requiring an owned copy. For example, this is [`str`] for
+    /// [`String`].
+    ///
+    /// Types that have no distinct borrowed form can use `Self`.
+    type Borrowed: ToOwned<Owned = Self> + Debug + Hash + Eq + ?Sized;
+    /// The representation stored in the pool. For example, this is
+    /// [`Box<str>`] for [`String`].
+    ///
+    /// This type's [`Hash`] and [`Eq`] implementations must be consistent with
+    /// the implementations on [`Self::Borrowed`](Self::Borrowed).
+    type Boxed: Borrow<Self::Borrowed> + Debug + Clone + Eq + Hash + Ord;
 
+    /// Converts this value into the representation stored in the pool.
     fn boxed(self) -> Self::Boxed;
+
+    /// Returns the number of bytes `value` occupies when stored in a pool.
+    ///
+    /// This is used to enforce [`PoolLimits::max_bytes`] and to report
+    /// [`MemoryUsage`]. The default implementation returns
+    /// [`size_of_val()`](std::mem::size_of_val), which is correct for the
+    /// built-in types because their borrowed forms are unsized slices.
+    ///
+    /// **The default ignores any heap data `value` owns.** Types that own heap
+    /// allocations, such as a struct or enum containing a [`String`], must
+    /// override this function to include those bytes, otherwise limits and
+    /// memory usage undercount them.
+    fn byte_len(value: &Self::Borrowed) -> usize {
+        std::mem::size_of_val(value)
+    }
 }
 
 impl Poolable for String {
+    type Borrowed = str;
     type Boxed = Box<str>;
 
     fn boxed(self) -> Self::Boxed {
@@ -33,6 +149,7 @@ impl Poolable for String {
 }
 
 impl Poolable for PathBuf {
+    type Borrowed = Path;
     type Boxed = Box<Path>;
 
     fn boxed(self) -> Self::Boxed {
@@ -40,7 +157,26 @@ impl Poolable for PathBuf {
     }
 }
 
+impl Poolable for OsString {
+    type Borrowed = OsStr;
+    type Boxed = Box<OsStr>;
+
+    fn boxed(self) -> Self::Boxed {
+        self.into_boxed_os_str()
+    }
+}
+
+impl Poolable for CString {
+    type Borrowed = CStr;
+    type Boxed = Box<CStr>;
+
+    fn boxed(self) -> Self::Boxed {
+        self.into_boxed_c_str()
+    }
+}
+
 impl Poolable for Vec<u8> {
+    type Borrowed = [u8];
     type Boxed = Box<[u8]>;
 
     fn boxed(self) -> Self::Boxed {
@@ -48,6 +184,358 @@ impl Poolable for Vec<u8> {
     }
 }
 
+/// The borrowed form of the values stored in a pool of kind `P`.
+pub type Borrowed<P, S> = <<P as PoolKindSealed<S>>::Owned as Poolable>::Borrowed;
+
+/// A key used to look up entries in [`Pool::active`] using the borrowed form
+/// of the pooled value.
+///
+/// `SharedData` can't implement `Borrow<Borrowed<P, S>>` directly, because it
+/// would conflict with the blanket `Borrow<T> for T` implementation. Instead,
+/// both `SharedData` and references to the borrowed value can be viewed as a
+/// `dyn LookupKey`.
+pub trait LookupKey<B: ?Sized> {
+    fn key(&self) -> &B;
+}
+
+impl<B: ?Sized> LookupKey<B> for &B {
+    fn key(&self) -> &B {
+        self
+    }
+}
+
+impl<B> Hash for dyn LookupKey<B> + '_
+where
+    B: Hash + ?Sized,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl<B> PartialEq for dyn LookupKey<B> + '_
+where
+    B: Eq + ?Sized,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<B> Eq for dyn LookupKey<B> + '_ where B: Eq + ?Sized {}
+
+thread_local! {
+    /// The addresses of the pools that are currently locked by this thread.
+    static LOCKED_POOLS: RefCell<Vec<*const ()>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns true if the pool at `address` is locked by the current thread.
+fn is_locked_by_current_thread(address: *const ()) -> bool {
+    LOCKED_POOLS
+        .try_with(|locked| locked.borrow().contains(&address))
+        .unwrap_or(false)
+}
+
+/// Records that the current thread holds the lock of the pool at an address
+/// until it is dropped.
+struct LockedPool(*const ());
+
+impl LockedPool {
+    fn new(address: *const ()) -> Self {
+        // If this thread's locals have already been destroyed, the thread is
+        // exiting and nothing is recorded.
+        let _ = LOCKED_POOLS.try_with(|locked| locked.borrow_mut().push(address));
+        Self(address)
+    }
+}
+
+impl Drop for LockedPool {
+    fn drop(&mut self) {
+        let _ = LOCKED_POOLS.try_with(|locked| {
+            let mut locked = locked.borrow_mut();
+            if let Some(index) = locked.iter().rposition(|address| *address == self.0) {
+                locked.remove(index);
+            }
+        });
+    }
+}
+
+/// The minimum time spent waiting for a pool's lock, in nanoseconds, that is
+/// reported as a `tracing` event.
+#[cfg(feature = "tracing")]
+static LOCK_WAIT_THRESHOLD: AtomicU64 = AtomicU64::new(1_000_000);
+
+/// Sets the minimum time a thread must wait for a pool's lock before the wait
+/// is reported as a `tracing` event. The default threshold is one millisecond.
+#[cfg(feature = "tracing")]
+pub fn set_lock_wait_threshold(threshold: Duration) {
+    LOCK_WAIT_THRESHOLD.store(
+        u64::try_from(threshold.as_nanos()).unwrap_or(u64::MAX),
+        atomic::Ordering::Relaxed,
+    );
+}
+
+/// Locks `mutex`, which protects `pool`, recovering from poisoning.
+///
+/// Waiting longer than the lock wait threshold is reported as a `tracing`
+/// event.
+#[cfg(feature = "tracing")]
+pub fn lock<'a, P, S, T>(pool: &P, mutex: &'a Mutex<T>) -> MutexGuard<'a, T>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    // Uncontended locks are acquired without reading the clock.
+    match mutex.try_lock() {
+        Ok(guard) => return guard,
+        Err(TryLockError::Poisoned(poisoned)) => return poisoned.into_inner(),
+        Err(TryLockError::WouldBlock) => {}
+    }
+    let start = Instant::now();
+    let guard = mutex.lock().unwrap_or_else(PoisonError::into_inner);
+    let waited = start.elapsed();
+    if waited.as_nanos() >= u128::from(LOCK_WAIT_THRESHOLD.load(atomic::Ordering::Relaxed)) {
+        tracing::debug!(
+            pool = pool.pool_name().unwrap_or_default(),
+            ?waited,
+            "waited for pool lock"
+        );
+    }
+    guard
+}
+
+/// Locks `mutex`, which protects `pool`, recovering from poisoning.
+#[cfg(not(feature = "tracing"))]
+pub fn lock<'a, P, S, T>(_pool: &P, mutex: &'a Mutex<T>) -> MutexGuard<'a, T>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Switches `pool` to releasing values immediately, and removes the values
+/// whose releases were deferred.
+///
+/// If the current thread holds the pool's lock, the queued values are removed
+/// when the lock is released.
+pub fn stop_deferring<P, S>(pool: &P)
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    let releases = pool.release_queue();
+    releases.set_mode(ReleaseMode::Immediate);
+    if !is_locked_by_current_thread(pool.address_of()) {
+        pool.with_active_symbols(|symbols| symbols.collect(releases));
+    }
+}
+
+/// Invokes `logic` with the symbols returned by `symbols`, which must belong
+/// to `pool`. `guard` must have just been acquired by the current thread, and
+/// it is dropped before this function returns.
+///
+/// Every [`PoolKindSealed`] implementation must invoke callbacks using this
+/// function. Values dropped while the pool is locked can't lock it again, so
+/// their releases are queued. Unless releases are being deferred, these are
+/// collected after `logic` returns. Once the pool is unlocked, its observers
+/// are notified of the changes made while it was locked.
+pub fn with_locked<P, S, G, T>(
+    pool: &P,
+    mut guard: G,
+    symbols: impl Fn(&mut G) -> &mut Pool<P, S>,
+    logic: impl FnOnce(&mut Pool<P, S>) -> T,
+) -> T
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    let locked = LockedPool::new(pool.address_of());
+    let result = logic(symbols(&mut guard));
+    unlock(pool, locked, guard, symbols);
+    result
+}
+
+/// Finishes the work started by [`with_locked()`] or [`PoolSnapshot::new()`]:
+/// collects the queued releases, drops `guard`, and notifies the pool's
+/// observers.
+fn unlock<P, S, G>(
+    pool: &P,
+    locked: LockedPool,
+    mut guard: G,
+    symbols: impl Fn(&mut G) -> &mut Pool<P, S>,
+) where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    let symbols = symbols(&mut guard);
+    let releases = pool.release_queue();
+    if !releases.is_deferred() {
+        symbols.collect(releases);
+    }
+    let events = mem::take(&mut symbols.events);
+    // Events are queued before the pool is unlocked, which ensures they are
+    // queued in the order they occurred.
+    let observers = pool.observers().filter(|_| !events.is_empty());
+    if let Some(observers) = observers {
+        observers.enqueue(events);
+    }
+    drop(locked);
+    drop(guard);
+
+    if let Some(observers) = observers {
+        observers.deliver();
+    }
+}
+
+/// A held lock on a pool's values.
+pub trait PoolGuard<P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    /// Returns the locked pool.
+    fn symbols(&self) -> &Pool<P, S>;
+    /// Returns the locked pool mutably.
+    fn symbols_mut(&mut self) -> &mut Pool<P, S>;
+}
+
+impl<P, S> PoolGuard<P, S> for MutexGuard<'_, Pool<P, S>>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    fn symbols(&self) -> &Pool<P, S> {
+        self
+    }
+
+    fn symbols_mut(&mut self) -> &mut Pool<P, S> {
+        self
+    }
+}
+
+/// The values of a pool, borrowed while the pool is locked.
+///
+/// Returned by `iter_snapshot()`. The pool remains locked until the snapshot
+/// is dropped, so values can't be looked up or inserted in the pool from the
+/// thread holding the snapshot. Dropping [`Pooled`] values from the pool is
+/// allowed; they are removed once the snapshot is dropped.
+pub struct PoolSnapshot<'a, P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    pool: P,
+    guard: Option<(LockedPool, Box<dyn PoolGuard<P, S> + 'a>)>,
+}
+
+impl<'a, P, S> PoolSnapshot<'a, P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    /// Returns a snapshot of the pool locked by `guard`, which must belong to
+    /// `pool` and must have just been acquired by the current thread.
+    pub(crate) fn new(pool: P, guard: impl PoolGuard<P, S> + 'a) -> Self {
+        let locked = LockedPool::new(pool.address_of());
+        Self {
+            pool,
+            guard: Some((locked, Box::new(guard))),
+        }
+    }
+
+    fn symbols(&self) -> &Pool<P, S> {
+        match &self.guard {
+            Some((_, guard)) => guard.symbols(),
+            None => unreachable!("the guard is only taken when dropped"),
+        }
+    }
+
+    /// Returns an iterator over the values in the pool, in no particular
+    /// order.
+    #[must_use]
+    pub fn iter(&self) -> SnapshotIter<'_, P, S> {
+        SnapshotIter(self.symbols().active.iter())
+    }
+
+    /// Returns the number of values in the pool.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.symbols().len()
+    }
+
+    /// Returns true if the pool contains no values.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<P, S> Debug for PoolSnapshot<'_, P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<P, S> Drop for PoolSnapshot<'_, P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    fn drop(&mut self) {
+        if let Some((locked, guard)) = self.guard.take() {
+            unlock(&self.pool, locked, guard, |guard| guard.symbols_mut());
+        }
+    }
+}
+
+impl<'s, P, S> IntoIterator for &'s PoolSnapshot<'_, P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    type Item = &'s P::Pooled;
+    type IntoIter = SnapshotIter<'s, P, S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the values of a [`PoolSnapshot`].
+#[derive(Debug)]
+pub struct SnapshotIter<'s, P, S>(std::collections::hash_set::Iter<'s, SharedData<P, S>>)
+where
+    P: PoolKind<S>,
+    S: BuildHasher;
+
+impl<'s, P, S> Iterator for SnapshotIter<'s, P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    type Item = &'s P::Pooled;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|data| &data.0.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<P, S> ExactSizeIterator for SnapshotIter<'_, P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+}
+
 #[derive(Debug)]
 pub struct SharedData<P, S>(pub Arc<Data<P, S>>)
 where
@@ -91,33 +579,23 @@ where
     }
 }
 
-impl<P, S> Borrow<str> for SharedData<P, S>
-where
-    P: PoolKind<S, Pooled = Box<str>>,
-    S: BuildHasher,
-{
-    fn borrow(&self) -> &str {
-        &self.0.value
-    }
-}
-
-impl<P, S> Borrow<Path> for SharedData<P, S>
+impl<P, S> LookupKey<Borrowed<P, S>> for SharedData<P, S>
 where
-    P: PoolKind<S, Pooled = Box<Path>>,
+    P: PoolKind<S>,
     S: BuildHasher,
 {
-    fn borrow(&self) -> &Path {
-        &self.0.value
+    fn key(&self) -> &Borrowed<P, S> {
+        Borrow::<Borrowed<P, S>>::borrow(&self.0.value)
     }
 }
 
-impl<P, S> Borrow<[u8]> for SharedData<P, S>
+impl<'a, P, S> Borrow<dyn LookupKey<Borrowed<P, S>> + 'a> for SharedData<P, S>
 where
-    P: PoolKind<S, Pooled = Box<[u8]>>,
-    S: BuildHasher,
+    P: PoolKind<S> + 'a,
+    S: BuildHasher + 'a,
 {
-    fn borrow(&self) -> &[u8] {
-        &self.0.value
+    fn borrow(&self) -> &(dyn LookupKey<Borrowed<P, S>> + 'a) {
+        self
     }
 }
 
@@ -134,7 +612,15 @@ where
         // We can use any form of atomics here because if the strong count is 3,
         // we can be guaranteed the only thread able to free our data is this
         // thread.
+        //
+        // Weak references don't contribute to the strong count. Upgrading a
+        // weak reference happens while the pool is locked, so the check of the
+        // strong count below also accounts for upgraded references.
+        //
+        // Permanent entries are never freed. The flag is only ever set while
+        // the pool is locked, so it is checked again below.
         if Arc::strong_count(&self.0) == 3
+            && !self.0.permanent.load(atomic::Ordering::Relaxed)
             && self
                 .0
                 .freeing
@@ -146,17 +632,42 @@ where
                 )
                 .is_ok()
         {
-            self.0.pool.with_active_symbols(|symbols| {
-                // Check that the strong count hasn't changed. If it has, we
-                // need to allow the symbol to stay alive.
-                if Arc::strong_count(&self.0) > 3 {
-                    self.0.freeing.store(false, atomic::Ordering::Relaxed);
-                } else {
-                    symbols.active.remove(self);
-                    symbols.slots[self.0.index] = None;
-                    symbols.free_slots.push(self.0.index);
+            let releases = self.0.pool.release_queue();
+            if is_locked_by_current_thread(self.0.pool.address_of()) {
+                // This thread already holds the pool's lock, for example
+                // because this value was dropped from within a callback.
+                // Locking it again would deadlock, so the release is queued
+                // and collected before the pool is unlocked.
+                releases.push(&self.0);
+            } else if releases.is_deferred() {
+                // Deferred releases never wait for the pool's lock. Once
+                // enough releases are queued, the queue is collected along
+                // with this value if the lock is available.
+                let collected = releases.threshold_reached()
+                    && self
+                        .0
+                        .pool
+                        .try_with_active_symbols(|symbols| {
+                            symbols.collect(releases);
+                            symbols.release(self);
+                        })
+                        .is_some();
+                if !collected {
+                    releases.push(&self.0);
+                    // The pool may have stopped deferring releases while this
+                    // value was being queued, in which case nothing else is
+                    // guaranteed to collect it.
+                    if !releases.is_deferred() {
+                        self.0
+                            .pool
+                            .with_active_symbols(|symbols| symbols.release(self));
+                    }
                 }
-            });
+            } else {
+                self.0
+                    .pool
+                    .with_active_symbols(|symbols| symbols.release(self));
+            }
         }
     }
 }
@@ -169,6 +680,12 @@ where
     pub index: usize,
     pub value: P::Pooled,
     pub freeing: AtomicBool,
+    pub permanent: AtomicBool,
+    /// The number of `StaticPooled*` values holding a reference to this value.
+    pub statics: AtomicUsize,
+    /// The backtraces of the live [`Pooled`] handles to this value.
+    #[cfg(feature = "track-holders")]
+    pub holders: Arc<Holders>,
     pub pool: P,
     _hasher: PhantomData<S>,
 }
@@ -182,6 +699,12 @@ where
     pub active: HashSet<SharedData<P, S>, S>,
     pub slots: Vec<Option<Pooled<P, S>>>,
     pub free_slots: Vec<usize>,
+    /// The total [`Poolable::byte_len()`] of the values in this pool.
+    pub bytes: usize,
+    #[cfg(feature = "stats")]
+    pub stats: PoolStats,
+    /// Changes that the pool's observers have not been notified of yet.
+    pub events: Vec<PendingEvent<P, S>>,
 }
 
 impl<P, S> Pool<P, S>
@@ -194,40 +717,342 @@ where
             active: HashSet::with_capacity_and_hasher(capacity, hasher),
             slots: Vec::with_capacity(capacity),
             free_slots: Vec::new(),
+            bytes: 0,
+            #[cfg(feature = "stats")]
+            stats: PoolStats::new(),
+            events: Vec::new(),
         }
     }
 
-    pub fn get<K>(&mut self, pooled: Cow<'_, K>, pool: &P) -> Pooled<P, S>
-    where
-        K: ToOwned<Owned = P::Owned> + Hash + Eq + ?Sized,
-        P::Owned: Borrow<K> + PartialEq<K>,
-        SharedData<P, S>: Borrow<K>,
-    {
-        if let Some(symbol) = self.active.get(pooled.as_ref()).cloned() {
-            Pooled(symbol)
-        } else {
-            let value = pooled.into_owned();
+    pub fn values(&self) -> impl Iterator<Item = &P::Pooled> {
+        self.active.iter().map(|data| &data.0.value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    pub fn capacity(&self) -> usize {
+        // Freed slots are reused before the slots vec grows, so the vec's
+        // capacity is the number of entries it can hold without reallocating.
+        self.active.capacity().min(self.slots.capacity())
+    }
 
-            let index = if let Some(free_slot) = self.free_slots.pop() {
-                free_slot
+    pub fn reserve(&mut self, additional: usize) {
+        self.active.reserve(additional);
+        self.slots
+            .reserve(additional.saturating_sub(self.free_slots.len()));
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        while matches!(self.slots.last(), Some(None)) {
+            self.slots.pop();
+        }
+        let slot_count = self.slots.len();
+        self.free_slots.retain(|index| *index < slot_count);
+
+        self.active.shrink_to_fit();
+        self.slots.shrink_to_fit();
+        self.free_slots.shrink_to_fit();
+    }
+
+    pub fn contains(&mut self, value: &Borrowed<P, S>) -> bool {
+        let key: &dyn LookupKey<Borrowed<P, S>> = &value;
+        let found = self.active.contains(key);
+        #[cfg(feature = "stats")]
+        self.stats.record_lookup(found);
+        found
+    }
+
+    #[cfg(feature = "track-holders")]
+    pub fn holders_of(&self, value: &Borrowed<P, S>) -> Vec<Arc<Backtrace>> {
+        let key: &dyn LookupKey<Borrowed<P, S>> = &value;
+        self.active
+            .get(key)
+            .map(|data| data.0.holders.backtraces())
+            .unwrap_or_default()
+    }
+
+    pub fn get_existing(&mut self, value: &Borrowed<P, S>) -> Option<Pooled<P, S>> {
+        let key: &dyn LookupKey<Borrowed<P, S>> = &value;
+        let found = self.active.get(key).cloned().map(Pooled::new);
+        #[cfg(feature = "stats")]
+        self.stats.record_lookup(found.is_some());
+        found
+    }
+
+    pub fn get(&mut self, pooled: Cow<'_, Borrowed<P, S>>, pool: &P) -> Pooled<P, S> {
+        match self.try_get(pooled, pool) {
+            Ok(pooled) => pooled,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    pub fn try_get(
+        &mut self,
+        pooled: Cow<'_, Borrowed<P, S>>,
+        pool: &P,
+    ) -> Result<Pooled<P, S>, Error> {
+        self.collect(pool.release_queue());
+
+        let key: &dyn LookupKey<Borrowed<P, S>> = &pooled.as_ref();
+        if let Some(symbol) = self.active.get(key).cloned() {
+            #[cfg(feature = "stats")]
+            self.stats.record_lookup(true);
+            Ok(Pooled::new(symbol))
+        } else {
+            #[cfg(feature = "stats")]
+            self.stats.record_lookup(false);
+            // Everything that can panic or fail happens before the pool is
+            // modified, which keeps the pool consistent if a panic unwinds
+            // through this function.
+            let byte_len = P::Owned::byte_len(&pooled);
+            // Sharded pools enforce their limits across all of their shards.
+            let reservation = if let Some(totals) = pool.shard_totals() {
+                Some(totals.reserve(pool.enforced_limits(), byte_len)?)
             } else {
-                let slot_id = self.slots.len();
-                self.slots.push(None);
-                slot_id
+                if let Some(limits) = pool.enforced_limits() {
+                    limits.check(self.active.len(), self.bytes, byte_len)?;
+                }
+                None
             };
 
-            let symbol = Pooled(SharedData(Arc::new(Data {
+            #[cfg(feature = "tracing")]
+            let capacity = self.capacity();
+            let index = self.free_slots.last().copied().unwrap_or(self.slots.len());
+            if index == self.slots.len() {
+                self.slots.try_reserve(1)?;
+            }
+            self.active.try_reserve(1)?;
+
+            let symbol = Pooled::new(SharedData(Arc::new(Data {
                 index,
-                value: value.boxed(),
+                value: pooled.into_owned().boxed(),
                 freeing: AtomicBool::new(false),
-                pool: pool.clone(),
+                permanent: AtomicBool::new(false),
+                statics: AtomicUsize::new(0),
+                #[cfg(feature = "track-holders")]
+                holders: Arc::default(),
+                pool: pool.clone_for_value(),
                 _hasher: PhantomData,
             })));
             self.active.insert(symbol.0.clone());
-            self.slots[index] = Some(symbol.clone());
-            symbol
+
+            if index == self.slots.len() {
+                self.slots.push(None);
+            } else {
+                self.free_slots.pop();
+                #[cfg(feature = "stats")]
+                {
+                    self.stats.slot_reuses += 1;
+                }
+            }
+            self.slots[index] = Some(Pooled::held_by_pool(symbol.0.clone()));
+            self.bytes += byte_len;
+            if let Some(reservation) = reservation {
+                reservation.commit();
+            }
+            #[cfg(feature = "stats")]
+            self.stats.record_insert(self.active.len());
+            if is_observed(pool) {
+                self.events.push(PendingEvent::Inserted(symbol.0.clone()));
+            }
+            #[cfg(feature = "tracing")]
+            {
+                let name = pool.pool_name().unwrap_or_default();
+                tracing::trace!(pool = name, index, bytes = byte_len, "inserted value");
+                if self.capacity() > capacity {
+                    tracing::debug!(
+                        pool = name,
+                        previous = capacity,
+                        capacity = self.capacity(),
+                        "pool capacity grew"
+                    );
+                }
+            }
+            Ok(symbol)
+        }
+    }
+
+    /// Removes `data` from this pool, unless another reference to it was
+    /// created after its release began.
+    fn release(&mut self, data: &SharedData<P, S>) {
+        // Check that the strong count hasn't changed. If it has, we need to
+        // allow the symbol to stay alive.
+        if Arc::strong_count(&data.0) > 3 || data.0.permanent.load(atomic::Ordering::Relaxed) {
+            data.0.freeing.store(false, atomic::Ordering::Relaxed);
+        } else {
+            self.active.remove(data);
+            self.slots[data.0.index] = None;
+            self.free_slots.push(data.0.index);
+            self.record_free(&data.0.pool, P::Owned::byte_len(data.0.value.borrow()));
+            if is_observed(&data.0.pool) {
+                self.events.push(PendingEvent::Freed(data.0.clone()));
+            }
+            #[cfg(feature = "tracing")]
+            tracing::trace!(
+                pool = data.0.pool.pool_name().unwrap_or_default(),
+                index = data.0.index,
+                "freed value"
+            );
+        }
+    }
+
+    fn record_free(&mut self, pool: &P, byte_len: usize) {
+        self.bytes -= byte_len;
+        #[cfg(feature = "stats")]
+        {
+            self.stats.frees += 1;
+        }
+        if let Some(totals) = pool.shard_totals() {
+            totals.freed(byte_len);
         }
     }
+
+    /// Removes every value in `releases` that is still unreferenced, returning
+    /// the number of values removed.
+    pub fn collect(&mut self, releases: &ReleaseQueue<P, S>) -> usize {
+        releases
+            .take()
+            .into_iter()
+            .filter(|data| self.release_deferred(data))
+            .count()
+    }
+
+    /// Removes a value whose release was deferred, returning true if it was
+    /// removed.
+    ///
+    /// The pool itself holds two strong references to each value. While the
+    /// pool is locked, new references can only be created by cloning an
+    /// existing reference, so a strong count of 2 means the value can be
+    /// removed.
+    fn release_deferred(&mut self, data: &Weak<Data<P, S>>) -> bool {
+        loop {
+            let Some(strong) = data.upgrade() else {
+                return false;
+            };
+            if strong.permanent.load(atomic::Ordering::Relaxed) {
+                strong.freeing.store(false, atomic::Ordering::Relaxed);
+                return false;
+            } else if Arc::strong_count(&strong) == 3 {
+                let value: &Borrowed<P, S> = strong.value.borrow();
+                let key: &dyn LookupKey<Borrowed<P, S>> = &value;
+                self.active.remove(key);
+                self.slots[strong.index] = None;
+                self.free_slots.push(strong.index);
+                self.record_free(&strong.pool, P::Owned::byte_len(value));
+                #[cfg(feature = "tracing")]
+                tracing::trace!(
+                    pool = strong.pool.pool_name().unwrap_or_default(),
+                    index = strong.index,
+                    "freed value"
+                );
+                if is_observed(&strong.pool) {
+                    self.events.push(PendingEvent::Freed(strong));
+                }
+                return true;
+            }
+
+            // The value is still in use. Clearing the freeing flag allows the
+            // final reference to release it again when it is dropped.
+            strong.freeing.store(false, atomic::Ordering::Relaxed);
+            drop(strong);
+
+            // If the final reference was dropped while we held our reference,
+            // it saw an extra reference and left the value in the pool. In
+            // that case, the value must be removed here instead, unless
+            // another thread has already begun releasing it again.
+            let orphaned = data.strong_count() == 2
+                && data.upgrade().is_some_and(|strong| {
+                    strong
+                        .freeing
+                        .compare_exchange(
+                            false,
+                            true,
+                            atomic::Ordering::Relaxed,
+                            atomic::Ordering::Relaxed,
+                        )
+                        .is_ok()
+                });
+            if !orphaned {
+                return false;
+            }
+        }
+    }
+
+    /// Upgrades `data` into a [`Pooled`] value if it is still the live entry
+    /// in its slot.
+    ///
+    /// A weak reference can still be upgraded after the entry has been
+    /// removed from the pool if another thread is still finishing dropping
+    /// the final strong reference. Checking the slot while the pool is locked
+    /// prevents handing out a value whose index has been released.
+    pub fn upgrade(&self, data: Arc<Data<P, S>>) -> Option<Pooled<P, S>> {
+        match self.slots.get(data.index) {
+            Some(Some(slot)) if Arc::ptr_eq(&slot.0 .0, &data) => {
+                Some(Pooled::new(SharedData(data)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the value stored in slot `index`, if any.
+    pub fn resolve(&self, index: usize) -> Option<Pooled<P, S>> {
+        self.slots.get(index).cloned().flatten()
+    }
+
+    /// Marks `pooled` as permanent, preventing it from ever being removed from
+    /// this pool.
+    ///
+    /// This must only be called while the pool is locked, as `SharedData::drop`
+    /// relies on the lock to observe this flag.
+    #[allow(clippy::unused_self)] // Requiring &mut self ensures the pool is locked.
+    pub fn make_permanent(&mut self, pooled: &Pooled<P, S>) {
+        pooled.0 .0.permanent.store(true, atomic::Ordering::Relaxed);
+    }
+
+    pub fn memory_usage(&self) -> MemoryUsage {
+        // Each `Arc` allocation stores its strong and weak counts before the
+        // data.
+        let header = 2 * mem::size_of::<usize>() + mem::size_of::<Data<P, S>>();
+        let unshared = self
+            .active
+            .iter()
+            .map(|data| {
+                // The pool holds two references to each value.
+                let references = Arc::strong_count(&data.0).saturating_sub(2);
+                references * P::Owned::byte_len(data.0.value.borrow())
+            })
+            .sum();
+
+        MemoryUsage {
+            values: self.bytes,
+            headers: self.active.len() * header,
+            table: hash_table_size::<SharedData<P, S>>(self.active.capacity()),
+            slots: self.slots.capacity() * mem::size_of::<Option<Pooled<P, S>>>()
+                + self.free_slots.capacity() * mem::size_of::<usize>(),
+            unshared,
+        }
+    }
+}
+
+/// Estimates the number of bytes allocated by a `HashSet<T>` with `capacity`.
+///
+/// The standard library's hash table allocates a power-of-two number of
+/// buckets that are at most 7/8ths full, with one control byte per bucket and
+/// an additional group of control bytes.
+fn hash_table_size<T>(capacity: usize) -> usize {
+    const GROUP_WIDTH: usize = 16;
+    if capacity == 0 {
+        0
+    } else {
+        let buckets = if capacity < 8 {
+            (capacity + 1).next_power_of_two()
+        } else {
+            (capacity * 8 / 7).next_power_of_two()
+        };
+        buckets * (mem::size_of::<T>() + 1) + GROUP_WIDTH
+    }
 }
 
 impl<P> Default for Pool<P, RandomState>
@@ -239,6 +1064,747 @@ where
             active: HashSet::with_hasher(RandomState::default()),
             slots: Vec::new(),
             free_slots: Vec::new(),
+            bytes: 0,
+            #[cfg(feature = "stats")]
+            stats: PoolStats::new(),
+            events: Vec::new(),
+        }
+    }
+}
+
+/// Controls when values that are no longer referenced are removed from a
+/// pool.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum ReleaseMode {
+    /// Values are removed from the pool as soon as their final reference is
+    /// dropped. This requires locking the pool each time a value is released.
+    #[default]
+    Immediate,
+    /// Values whose final reference is dropped are placed in a queue without
+    /// locking the pool. Queued values are removed in batches: when the pool
+    /// next retrieves a value, when it is explicitly collected, or once
+    /// `threshold` values are queued.
+    ///
+    /// When the threshold is reached, the queue is only collected if the pool
+    /// is not currently locked. Dropping a value never waits for the pool's
+    /// lock in this mode. A threshold of 0 is treated as 1.
+    Deferred {
+        /// The number of queued values that causes the queue to be collected
+        /// when another value is released.
+        threshold: usize,
+    },
+}
+
+/// Returns true if `pool` has any observers.
+fn is_observed<P, S>(pool: &P) -> bool
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    pool.observers()
+        .is_some_and(|observers| !observers.is_empty())
+}
+
+/// A change to a pool that its observers have not been notified of yet.
+///
+/// Inserted values are held as a [`SharedData`], which releases the value if
+/// its other references are dropped before the observers are notified.
+#[derive(Debug)]
+pub enum PendingEvent<P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    Inserted(SharedData<P, S>),
+    Freed(Arc<Data<P, S>>),
+}
+
+/// A callback that is notified of changes to a pool.
+type Observer<P, S> = Arc<dyn Fn(&PoolEvent<'_, P, S>) + Send + Sync>;
+
+/// The callbacks that are notified of changes to a pool.
+pub struct Observers<P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    count: AtomicUsize,
+    registered: RwLock<Vec<Observer<P, S>>>,
+    /// Events that have not been delivered yet, in the order they occurred.
+    pending: Mutex<VecDeque<PendingEvent<P, S>>>,
+    /// Whether a thread is currently delivering the pending events.
+    delivering: AtomicBool,
+}
+
+impl<P, S> Observers<P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    pub const fn new() -> Self {
+        Self {
+            count: AtomicUsize::new(0),
+            registered: RwLock::new(Vec::new()),
+            pending: Mutex::new(VecDeque::new()),
+            delivering: AtomicBool::new(false),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count.load(atomic::Ordering::Relaxed) == 0
+    }
+
+    pub fn add(&self, observer: Observer<P, S>) {
+        let mut registered = self
+            .registered
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        registered.push(observer);
+        self.count
+            .store(registered.len(), atomic::Ordering::Relaxed);
+    }
+
+    pub fn clear(&self) {
+        let mut registered = self
+            .registered
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        registered.clear();
+        self.count.store(0, atomic::Ordering::Relaxed);
+    }
+
+    /// Queues `events` to be delivered by [`Observers::deliver()`].
+    ///
+    /// This must be called while the pool that `events` occurred in is still
+    /// locked, so that events are queued in the order they occurred.
+    fn enqueue(&self, events: Vec<PendingEvent<P, S>>) {
+        self.pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend(events);
+    }
+
+    /// Notifies each observer of the queued events, in the order they were
+    /// queued.
+    ///
+    /// This must be called after the pool is unlocked, which allows observers
+    /// to use the pool. Only one thread delivers events at a time: if another
+    /// thread is already delivering, it also delivers the events queued by
+    /// this thread, and this function returns immediately. Events raised by
+    /// an observer are delivered after the events that are already queued.
+    ///
+    /// The observers are copied before any are invoked, so observers can also
+    /// be added or cleared from within an observer.
+    fn deliver(&self) {
+        while !self.delivering.swap(true, atomic::Ordering::Acquire) {
+            let delivering = Delivering(&self.delivering);
+            let observers = self
+                .registered
+                .read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone();
+            while let Some(event) = self.next_pending() {
+                let (kind, data) = match &event {
+                    PendingEvent::Inserted(data) => (PoolEventKind::Inserted, &data.0),
+                    PendingEvent::Freed(data) => (PoolEventKind::Freed, data),
+                };
+                let event = PoolEvent {
+                    kind,
+                    value: data.value.borrow(),
+                    index: data.index,
+                    pool: &data.pool,
+                    _hasher: PhantomData,
+                };
+                for observer in &observers {
+                    observer(&event);
+                }
+            }
+            drop(delivering);
+
+            // Another thread may have queued events after the queue was found
+            // empty, but before delivery stopped.
+            if self
+                .pending
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .is_empty()
+            {
+                break;
+            }
+        }
+    }
+
+    fn next_pending(&self) -> Option<PendingEvent<P, S>> {
+        self.pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front()
+    }
+}
+
+/// Marks an [`Observers`] as no longer delivering events when dropped, including
+/// when an observer panics.
+struct Delivering<'a>(&'a AtomicBool);
+
+impl Drop for Delivering<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, atomic::Ordering::Release);
+    }
+}
+
+impl<P, S> Debug for Observers<P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Observers")
+            .field("count", &self.count.load(atomic::Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+/// The kind of change described by a [`PoolEvent`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum PoolEventKind {
+    /// A new value was inserted into the pool.
+    Inserted,
+    /// A value was removed from the pool after its final reference was
+    /// dropped.
+    Freed,
+}
+
+/// A change to a pool's contents, passed to the observers registered with
+/// `add_observer()`.
+///
+/// Observers are invoked after the pool has been unlocked, so they can use the
+/// pool without deadlocking. Because of this, the pool may have changed again
+/// by the time an observer is invoked.
+///
+/// Events are delivered in the order they occurred in the pool, one at a time.
+/// When several threads change the pool at once, a single thread delivers the
+/// events for all of them, so an observer may be invoked on a different thread
+/// than the one that changed the pool, and the events may not have been
+/// delivered yet when the function that caused them returns. Events raised by
+/// an observer are delivered after the events that were already waiting to be
+/// delivered.
+///
+/// ```rust
+/// use std::sync::{Arc, Mutex};
+///
+/// use interner::shared::StringPool;
+/// use interner::PoolEventKind;
+///
+/// let pool = StringPool::default();
+/// let log = Arc::new(Mutex::new(Vec::new()));
+/// pool.add_observer({
+///     let log = log.clone();
+///     move |event| {
+///         log.lock()
+///             .unwrap()
+///             .push((event.kind, event.value.to_string(), event.index));
+///     }
+/// });
+///
+/// drop(pool.get("hello"));
+/// assert_eq!(
+///     log.lock().unwrap().as_slice(),
+///     &[
+///         (PoolEventKind::Inserted, String::from("hello"), 0),
+///         (PoolEventKind::Freed, String::from("hello"), 0),
+///     ]
+/// );
+/// ```
+pub struct PoolEvent<'a, P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    /// The kind of change that occurred.
+    pub kind: PoolEventKind,
+    /// The value that was inserted or freed.
+    pub value: &'a Borrowed<P, S>,
+    /// The index of the slot the value occupies, or occupied before it was
+    /// freed. Unless the pool is sharded, this is the same index a
+    /// [`Symbol`](crate::Symbol) for this value contains. For sharded pools,
+    /// this is the index within the value's shard.
+    pub index: usize,
+    /// The pool that changed.
+    pub pool: &'a P,
+    _hasher: PhantomData<S>,
+}
+
+impl<P, S> Debug for PoolEvent<'_, P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+    Borrowed<P, S>: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PoolEvent")
+            .field("kind", &self.kind)
+            .field("value", &self.value)
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+type ReleaseChannel<P, S> = (Sender<Weak<Data<P, S>>>, Mutex<Receiver<Weak<Data<P, S>>>>);
+
+/// A queue of values whose removal from a pool has been deferred.
+///
+/// Values are pushed onto the queue without locking the pool, and are removed
+/// from the pool in batches by [`Pool::collect()`]. The receiving half of the
+/// queue is only accessed while the pool is locked, so its lock is never
+/// contended.
+pub struct ReleaseQueue<P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    /// The number of queued releases that triggers a collection, or 0 if
+    /// values are released immediately.
+    threshold: AtomicUsize,
+    pending: AtomicUsize,
+    channel: OnceLock<ReleaseChannel<P, S>>,
+}
+
+impl<P, S> ReleaseQueue<P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    pub const fn new() -> Self {
+        Self {
+            threshold: AtomicUsize::new(0),
+            pending: AtomicUsize::new(0),
+            channel: OnceLock::new(),
+        }
+    }
+
+    pub fn mode(&self) -> ReleaseMode {
+        match self.threshold.load(atomic::Ordering::Relaxed) {
+            0 => ReleaseMode::Immediate,
+            threshold => ReleaseMode::Deferred { threshold },
+        }
+    }
+
+    pub fn set_mode(&self, mode: ReleaseMode) {
+        let threshold = match mode {
+            ReleaseMode::Immediate => 0,
+            ReleaseMode::Deferred { threshold } => threshold.max(1),
+        };
+        self.threshold.store(threshold, atomic::Ordering::SeqCst);
+    }
+
+    pub fn is_deferred(&self) -> bool {
+        self.threshold.load(atomic::Ordering::SeqCst) > 0
+    }
+
+    /// Returns true if releasing one more value reaches the threshold.
+    pub fn threshold_reached(&self) -> bool {
+        self.pending.load(atomic::Ordering::Relaxed) + 1
+            >= self.threshold.load(atomic::Ordering::Relaxed)
+    }
+
+    pub fn push(&self, data: &Arc<Data<P, S>>) {
+        let (sender, _) = self.channel.get_or_init(|| {
+            let (sender, receiver) = mpsc::channel();
+            (sender, Mutex::new(receiver))
+        });
+        self.pending.fetch_add(1, atomic::Ordering::Relaxed);
+        sender
+            .send(Arc::downgrade(data))
+            .expect("the receiver is owned by the queue");
+    }
+
+    fn take(&self) -> Vec<Weak<Data<P, S>>> {
+        // The pending count is incremented before a value is sent, so the
+        // channel is empty if nothing is pending.
+        let Some((_, receiver)) = self
+            .channel
+            .get()
+            .filter(|_| self.pending.load(atomic::Ordering::Relaxed) > 0)
+        else {
+            return Vec::new();
+        };
+        let taken = receiver
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .try_iter()
+            .collect::<Vec<_>>();
+        self.pending
+            .fetch_sub(taken.len(), atomic::Ordering::Relaxed);
+        taken
+    }
+}
+
+impl<P, S> Debug for ReleaseQueue<P, S>
+where
+    P: PoolKind<S>,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReleaseQueue")
+            .field("mode", &self.mode())
+            .field("pending", &self.pending.load(atomic::Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Limits on the contents of a pool.
+///
+/// Limits are enforced when inserting new values. Fallible functions such as
+/// [`SharedPool::get_checked()`](crate::shared::SharedPool::get_checked)
+/// return an error when a limit would be exceeded, while functions such as
+/// [`SharedPool::get()`](crate::shared::SharedPool::get) panic. Values that are
+/// already in the pool can always be retrieved, and lowering a limit never
+/// removes existing values.
+///
+/// ```rust
+/// use interner::shared::StringPool;
+/// use interner::{Error, PoolLimits};
+///
+/// let pool = StringPool::default().with_limits(
+///     PoolLimits::new()
+///         .with_max_entries(2)
+///         .with_max_value_len(5),
+/// );
+///
+/// assert!(pool.get_checked("hello").is_ok());
+/// assert_eq!(pool.get_checked("goodbye"), Err(Error::ValueTooLong));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct PoolLimits {
+    /// The maximum number of values the pool can contain.
+    pub max_entries: Option<usize>,
+    /// The maximum total [`Poolable::byte_len()`] of the values the pool can
+    /// contain.
+    pub max_bytes: Option<usize>,
+    /// The maximum [`Poolable::byte_len()`] of a single value.
+    pub max_value_len: Option<usize>,
+}
+
+impl PoolLimits {
+    /// Returns limits that do not restrict the pool.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_entries: None,
+            max_bytes: None,
+            max_value_len: None,
+        }
+    }
+
+    /// Limits the pool to containing at most `max_entries` values.
+    #[must_use]
+    pub const fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    /// Limits the total [`Poolable::byte_len()`] of the values the pool
+    /// contains to `max_bytes`.
+    #[must_use]
+    pub const fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Limits the [`Poolable::byte_len()`] of each value to `max_value_len`.
+    #[must_use]
+    pub const fn with_max_value_len(mut self, max_value_len: usize) -> Self {
+        self.max_value_len = Some(max_value_len);
+        self
+    }
+}
+
+/// [`PoolLimits`] that can be changed without locking the pool. A value of
+/// `usize::MAX` means the limit is not set.
+#[derive(Debug)]
+pub struct AtomicLimits {
+    entries: AtomicUsize,
+    bytes: AtomicUsize,
+    value_len: AtomicUsize,
+}
+
+impl AtomicLimits {
+    pub const fn new(limits: PoolLimits) -> Self {
+        const fn limit(value: Option<usize>) -> usize {
+            match value {
+                Some(value) => value,
+                None => usize::MAX,
+            }
+        }
+        Self {
+            entries: AtomicUsize::new(limit(limits.max_entries)),
+            bytes: AtomicUsize::new(limit(limits.max_bytes)),
+            value_len: AtomicUsize::new(limit(limits.max_value_len)),
+        }
+    }
+
+    pub fn get(&self) -> PoolLimits {
+        let limit = |value: &AtomicUsize| {
+            Some(value.load(atomic::Ordering::Relaxed)).filter(|value| *value != usize::MAX)
+        };
+        PoolLimits {
+            max_entries: limit(&self.entries),
+            max_bytes: limit(&self.bytes),
+            max_value_len: limit(&self.value_len),
+        }
+    }
+
+    pub fn set(&self, limits: PoolLimits) {
+        let limit = |value: &AtomicUsize, limit: Option<usize>| {
+            value.store(limit.unwrap_or(usize::MAX), atomic::Ordering::Relaxed);
+        };
+        limit(&self.entries, limits.max_entries);
+        limit(&self.bytes, limits.max_bytes);
+        limit(&self.value_len, limits.max_value_len);
+    }
+
+    /// Checks whether a value of `byte_len` bytes can be inserted into a pool
+    /// that contains `entries` values totaling `bytes` bytes.
+    fn check(&self, entries: usize, bytes: usize, byte_len: usize) -> Result<(), Error> {
+        if byte_len > self.value_len.load(atomic::Ordering::Relaxed) {
+            Err(Error::ValueTooLong)
+        } else if entries >= self.entries.load(atomic::Ordering::Relaxed) {
+            Err(Error::TooManyEntries)
+        } else if bytes.saturating_add(byte_len) > self.bytes.load(atomic::Ordering::Relaxed) {
+            Err(Error::TooManyBytes)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The values stored across all shards of a sharded pool, which are used to
+/// enforce the pool's limits and to track its peak number of values.
+///
+/// The totals are only locked while a value is inserted into or freed from
+/// one of the pool's shards, and never while any other lock is acquired.
+#[derive(Debug, Default)]
+pub struct ShardTotals(Mutex<Totals>);
+
+#[derive(Debug, Default)]
+struct Totals {
+    entries: usize,
+    bytes: usize,
+    #[cfg(feature = "stats")]
+    peak: usize,
+}
+
+impl ShardTotals {
+    pub const fn new() -> Self {
+        Self(Mutex::new(Totals {
+            entries: 0,
+            bytes: 0,
+            #[cfg(feature = "stats")]
+            peak: 0,
+        }))
+    }
+
+    fn totals(&self) -> MutexGuard<'_, Totals> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Reserves room for a value of `byte_len` bytes, checking it against
+    /// `limits`. The reservation is undone if it is dropped without being
+    /// committed.
+    fn reserve(
+        &self,
+        limits: Option<&AtomicLimits>,
+        byte_len: usize,
+    ) -> Result<ShardReservation<'_>, Error> {
+        let mut totals = self.totals();
+        if let Some(limits) = limits {
+            limits.check(totals.entries, totals.bytes, byte_len)?;
+        }
+        totals.entries += 1;
+        totals.bytes += byte_len;
+        Ok(ShardReservation {
+            totals: self,
+            byte_len,
+        })
+    }
+
+    fn freed(&self, byte_len: usize) {
+        let mut totals = self.totals();
+        totals.entries -= 1;
+        totals.bytes -= byte_len;
+    }
+
+    /// Returns the largest number of values the pool has contained at once.
+    #[cfg(feature = "stats")]
+    pub fn peak(&self) -> usize {
+        self.totals().peak
+    }
+}
+
+/// Room reserved in a [`ShardTotals`] for a value that is being inserted.
+struct ShardReservation<'a> {
+    totals: &'a ShardTotals,
+    byte_len: usize,
+}
+
+impl ShardReservation<'_> {
+    /// Keeps the reserved room, as the value has been inserted.
+    fn commit(self) {
+        #[cfg(feature = "stats")]
+        {
+            let mut totals = self.totals.totals();
+            totals.peak = totals.peak.max(totals.entries);
+        }
+        mem::forget(self);
+    }
+}
+
+impl Drop for ShardReservation<'_> {
+    fn drop(&mut self) {
+        self.totals.freed(self.byte_len);
+    }
+}
+
+/// Statistics about how a pool has been used since it was created.
+///
+/// Statistics are only recorded when the `stats` feature is enabled.
+#[cfg(feature = "stats")]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct PoolStats {
+    /// The number of times a value was looked up in the pool, including by
+    /// functions that never insert values, such as `get_existing()` and
+    /// `contains()`.
+    pub lookups: u64,
+    /// The number of lookups that found an existing value.
+    pub hits: u64,
+    /// The number of lookups that did not find an existing value.
+    pub misses: u64,
+    /// The number of lookups that were served from a thread's cache by
+    /// `GlobalPool::get_cached()` without locking the pool. These lookups are
+    /// also counted in [`lookups`](Self::lookups) and [`hits`](Self::hits).
+    pub cached_hits: u64,
+    /// The number of values inserted into the pool. This is less than
+    /// [`misses`](Self::misses) if a lookup did not insert its value, or if
+    /// inserting a value failed.
+    pub inserts: u64,
+    /// The number of values removed from the pool after their final reference
+    /// was dropped.
+    pub frees: u64,
+    /// The largest number of values the pool has contained at once.
+    pub peak_entries: usize,
+    /// The number of inserted values that reused the slot of a freed value.
+    pub slot_reuses: u64,
+}
+
+#[cfg(feature = "stats")]
+impl PoolStats {
+    const fn new() -> Self {
+        Self {
+            lookups: 0,
+            hits: 0,
+            misses: 0,
+            cached_hits: 0,
+            inserts: 0,
+            frees: 0,
+            peak_entries: 0,
+            slot_reuses: 0,
+        }
+    }
+
+    fn record_lookup(&mut self, found: bool) {
+        self.lookups += 1;
+        if found {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+    }
+
+    /// Records lookups served from a thread cache without locking the pool.
+    pub(crate) fn with_cached_hits(mut self, cached_hits: u64) -> Self {
+        self.lookups += cached_hits;
+        self.hits += cached_hits;
+        self.cached_hits += cached_hits;
+        self
+    }
+
+    fn record_insert(&mut self, entries: usize) {
+        self.inserts += 1;
+        self.peak_entries = self.peak_entries.max(entries);
+    }
+
+    /// Combines the statistics of separately locked shards. The peak of the
+    /// combined statistics is the largest peak of a single shard, as the
+    /// shards may have reached their peaks at different times.
+    pub(crate) fn merge(mut self, other: Self) -> Self {
+        self.lookups += other.lookups;
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.cached_hits += other.cached_hits;
+        self.inserts += other.inserts;
+        self.frees += other.frees;
+        self.peak_entries = self.peak_entries.max(other.peak_entries);
+        self.slot_reuses += other.slot_reuses;
+        self
+    }
+}
+
+/// An estimate of the memory used by a pool.
+///
+/// Returned by `memory_usage()` on each pool type. The sizes of the hash table
+/// and the pool's internal allocations are estimates, as the standard library
+/// does not expose the exact size of its allocations.
+///
+/// ```rust
+/// use interner::shared::StringPool;
+///
+/// let pool = StringPool::default();
+/// let copies = (0..10).map(|_| pool.get("hello")).collect::<Vec<_>>();
+///
+/// let usage = pool.memory_usage();
+/// assert_eq!(usage.values, 5);
+/// assert_eq!(usage.unshared, 50);
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct MemoryUsage {
+    /// The total [`Poolable::byte_len()`] of the pooled values.
+    pub values: usize,
+    /// The bytes used by the reference counts and bookkeeping stored alongside
+    /// each pooled value.
+    pub headers: usize,
+    /// The estimated bytes allocated by the hash table used to look up values.
+    pub table: usize,
+    /// The bytes allocated for the vectors that track each value's slot.
+    pub slots: usize,
+    /// The bytes the pooled values would occupy if each live reference owned
+    /// a separate copy of its value.
+    pub unshared: usize,
+}
+
+impl MemoryUsage {
+    /// Returns the total bytes used by the pool.
+    #[must_use]
+    pub const fn total(&self) -> usize {
+        self.values + self.headers + self.table + self.slots
+    }
+
+    /// Returns the number of bytes saved by pooling, compared to each live
+    /// reference owning a separate copy of its value.
+    ///
+    /// Returns 0 if the pool uses more memory than it saves.
+    #[must_use]
+    pub const fn saved(&self) -> usize {
+        self.unshared.saturating_sub(self.total())
+    }
+
+    /// Combines the memory usage of separately locked shards.
+    pub(crate) const fn merge(self, other: Self) -> Self {
+        Self {
+            values: self.values + other.values,
+            headers: self.headers + other.headers,
+            table: self.table + other.table,
+            slots: self.slots + other.slots,
+            unshared: self.unshared + other.unshared,
         }
     }
 }
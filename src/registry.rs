@@ -0,0 +1,128 @@
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+
+use crate::MemoryUsage;
+
+/// The pools that have been registered, in the order they were registered.
+static REGISTRY: Mutex<Vec<Registration>> = Mutex::new(Vec::new());
+
+/// Returns information about every live pool in the process that has been
+/// registered.
+///
+/// Pools are added to the registry by calling their `register()` function,
+/// such as [`SharedPool::register()`](crate::shared::SharedPool::register) or
+/// [`GlobalPool::register()`](crate::global::GlobalPool::register). Shared
+/// pools are listed until they are dropped. Pools that were not given a name
+/// have a [`PoolInfo::name`] of `None`.
+///
+/// Each pool is locked while its information is gathered. This function must
+/// not be called while the current thread is using a pool, such as from within
+/// a callback passed to `for_each()`.
+///
+/// ```rust
+/// use interner::global::GlobalPool;
+/// use interner::registry::{self, Kind};
+///
+/// static IDENTIFIERS: GlobalPool<String> = GlobalPool::named("identifiers");
+///
+/// IDENTIFIERS.register();
+/// let hello = IDENTIFIERS.get("hello");
+/// let info = registry::pools()
+///     .into_iter()
+///     .find(|info| info.name == Some("identifiers"))
+///     .unwrap();
+/// assert_eq!(info.kind, Kind::Global);
+/// assert_eq!(info.len, 1);
+/// ```
+#[must_use]
+pub fn pools() -> Vec<PoolInfo> {
+    // The registry is unlocked before any pool is locked, so that pools can
+    // be registered while another pool is locked.
+    let live = {
+        let mut registry = REGISTRY.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut live = Vec::with_capacity(registry.len());
+        registry.retain(|registration| match registration.upgrade() {
+            Some(pool) => {
+                live.push(pool);
+                true
+            }
+            None => false,
+        });
+        live
+    };
+    live.into_iter().map(Registered::info).collect()
+}
+
+/// Information about a pool, returned by [`pools()`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PoolInfo {
+    /// The name the pool was given, if any.
+    pub name: Option<&'static str>,
+    /// The kind of pool.
+    pub kind: Kind,
+    /// The name of the type of values stored in the pool, as returned by
+    /// [`std::any::type_name()`].
+    pub value_type: &'static str,
+    /// The number of values currently stored in the pool.
+    pub len: usize,
+    /// The memory used by the pool.
+    pub memory: MemoryUsage,
+}
+
+/// A kind of pool listed by [`pools()`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Kind {
+    /// A [`GlobalPool`](crate::global::GlobalPool).
+    Global,
+    /// A [`SharedPool`](crate::shared::SharedPool).
+    Shared,
+    /// A [`ShardedGlobalPool`](crate::sharded::ShardedGlobalPool).
+    ShardedGlobal,
+    /// A [`ShardedSharedPool`](crate::sharded::ShardedSharedPool).
+    ShardedShared,
+}
+
+/// A pool that can be listed by [`pools()`].
+pub(crate) trait Registered: Send + Sync {
+    fn info(self: Arc<Self>) -> PoolInfo;
+}
+
+enum Registration {
+    /// A global pool, which is never dropped.
+    Global(Arc<dyn Registered>),
+    /// A shared pool, which is listed until it is dropped.
+    Shared(Weak<dyn Registered>),
+}
+
+impl Registration {
+    fn upgrade(&self) -> Option<Arc<dyn Registered>> {
+        match self {
+            Registration::Global(pool) => Some(pool.clone()),
+            Registration::Shared(pool) => pool.upgrade(),
+        }
+    }
+
+    fn is_live(&self) -> bool {
+        match self {
+            Registration::Global(_) => true,
+            Registration::Shared(pool) => pool.strong_count() > 0,
+        }
+    }
+}
+
+/// Registers a global pool.
+pub(crate) fn register_global(pool: Arc<dyn Registered>) {
+    register(Registration::Global(pool));
+}
+
+/// Registers a shared pool, which is listed until it is dropped.
+pub(crate) fn register_shared(pool: Weak<dyn Registered>) {
+    register(Registration::Shared(pool));
+}
+
+/// Adds `registration` to the registry, removing any shared pools that have
+/// been dropped.
+fn register(registration: Registration) {
+    let mut registry = REGISTRY.lock().unwrap_or_else(PoisonError::into_inner);
+    registry.retain(Registration::is_live);
+    registry.push(registration);
+}
@@ -0,0 +1,1610 @@
+#[cfg(feature = "track-holders")]
+use std::backtrace::Backtrace;
+use std::borrow::Cow;
+use std::collections::hash_map::RandomState;
+use std::ffi::{CString, OsString};
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash};
+use std::path::PathBuf;
+#[cfg(feature = "registry")]
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, TryLockError};
+
+#[cfg(feature = "stats")]
+use crate::pool::PoolStats;
+use crate::pool::{
+    self, AtomicLimits, MemoryUsage, Observers, Pool, PoolEvent, PoolKindSealed, ReleaseQueue,
+    ShardTotals,
+};
+#[cfg(feature = "registry")]
+use crate::registry::{self, Kind, PoolInfo, Registered};
+use crate::shared::Handle;
+use crate::{
+    Entry, Error, PoolKind, PoolLimits, Poolable, Pooled, ReleaseMode, Symbol, VacantEntry,
+};
+
+/// The default number of shards used by [`ShardedGlobalPool`] and
+/// [`ShardedSharedPool`].
+pub const DEFAULT_SHARDS: usize = 16;
+
+/// A pooled string that is stored in a [`ShardedGlobalPool`].
+pub type ShardedGlobalString<S = RandomState, const N: usize = DEFAULT_SHARDS> =
+    Pooled<GlobalShard<String, S, N>, S>;
+/// A pooled path that is stored in a [`ShardedGlobalPool`].
+pub type ShardedGlobalPath<S = RandomState, const N: usize = DEFAULT_SHARDS> =
+    Pooled<GlobalShard<PathBuf, S, N>, S>;
+/// A pooled OS string that is stored in a [`ShardedGlobalPool`].
+pub type ShardedGlobalOsString<S = RandomState, const N: usize = DEFAULT_SHARDS> =
+    Pooled<GlobalShard<OsString, S, N>, S>;
+/// A pooled C string that is stored in a [`ShardedGlobalPool`].
+pub type ShardedGlobalCString<S = RandomState, const N: usize = DEFAULT_SHARDS> =
+    Pooled<GlobalShard<CString, S, N>, S>;
+/// A pooled buffer (`Vec<u8>`) that is stored in a [`ShardedGlobalPool`].
+pub type ShardedGlobalBuffer<S = RandomState, const N: usize = DEFAULT_SHARDS> =
+    Pooled<GlobalShard<Vec<u8>, S, N>, S>;
+
+/// A pooled string that belongs to a [`ShardedSharedPool`].
+pub type ShardedSharedString<S = RandomState, const N: usize = DEFAULT_SHARDS> =
+    Pooled<SharedShard<String, S, N>, S>;
+/// A pooled path that belongs to a [`ShardedSharedPool`].
+pub type ShardedSharedPath<S = RandomState, const N: usize = DEFAULT_SHARDS> =
+    Pooled<SharedShard<PathBuf, S, N>, S>;
+/// A pooled OS string that belongs to a [`ShardedSharedPool`].
+pub type ShardedSharedOsString<S = RandomState, const N: usize = DEFAULT_SHARDS> =
+    Pooled<SharedShard<OsString, S, N>, S>;
+/// A pooled C string that belongs to a [`ShardedSharedPool`].
+pub type ShardedSharedCString<S = RandomState, const N: usize = DEFAULT_SHARDS> =
+    Pooled<SharedShard<CString, S, N>, S>;
+/// A pooled buffer (`Vec<u8>`) that belongs to a [`ShardedSharedPool`].
+pub type ShardedSharedBuffer<S = RandomState, const N: usize = DEFAULT_SHARDS> =
+    Pooled<SharedShard<Vec<u8>, S, N>, S>;
+
+/// A global interning pool that is split into `N` independently locked
+/// shards.
+///
+/// Each value is assigned to a shard based on its hash. Threads working with
+/// values in different shards never wait on each other, which reduces lock
+/// contention compared to a [`GlobalPool`](crate::global::GlobalPool).
+///
+/// [`Pooled`] values from a sharded pool record which shard they belong to.
+/// [`Pooled::ptr_eq()`] and equality comparisons between values from the same
+/// pool only compare the shard and the index within the shard. A [`Symbol`]
+/// for a value in a sharded pool records both as well.
+///
+/// Sharded pools support names, observers, release modes, symbols, and
+/// [`PoolLimits`]. Limits apply to the values stored across all shards, which
+/// are counted separately from the shards: inserting or freeing a value
+/// briefly locks this count after locking the value's shard. Sharded pools
+/// cannot be frozen, and they do not support per-thread caches like
+/// [`GlobalPool::get_cached()`](crate::global::GlobalPool::get_cached).
+///
+/// ```rust
+/// use interner::sharded::{ShardedGlobalPool, ShardedGlobalString};
+///
+/// static STRINGS: ShardedGlobalPool<String> = ShardedGlobalPool::new();
+///
+/// let interned = STRINGS.get(String::from("hello"));
+/// let second = STRINGS.get("hello");
+///
+/// assert!(ShardedGlobalString::ptr_eq(&interned, &second));
+/// ```
+pub struct ShardedGlobalPool<T, S = RandomState, const N: usize = DEFAULT_SHARDS>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd + 'static,
+    S: BuildHasher + Clone + 'static,
+{
+    hasher: ShardHasher<S>,
+    capacity: usize,
+    shards: [GlobalShardState<T, S, N>; N],
+    observers: Observers<GlobalShard<T, S, N>, S>,
+    name: Option<&'static str>,
+    limits: AtomicLimits,
+    totals: ShardTotals,
+    /// Whether this pool has been added to the registry.
+    #[cfg(feature = "registry")]
+    registered: AtomicBool,
+}
+
+/// A shard of a [`ShardedGlobalPool`], which is initialized on first use.
+struct GlobalShardState<T, S, const N: usize>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd + 'static,
+    S: BuildHasher + Clone + 'static,
+{
+    pool: Mutex<Option<Pool<GlobalShard<T, S, N>, S>>>,
+    releases: ReleaseQueue<GlobalShard<T, S, N>, S>,
+}
+
+enum ShardHasher<S> {
+    Static(S),
+    Lazy {
+        init: fn() -> S,
+        hasher: OnceLock<S>,
+    },
+}
+
+impl<S> ShardHasher<S> {
+    fn get(&self) -> &S {
+        match self {
+            ShardHasher::Static(hasher) => hasher,
+            ShardHasher::Lazy { init, hasher } => hasher.get_or_init(init),
+        }
+    }
+}
+
+/// Returns the shard that `value` belongs to.
+///
+/// The shard is chosen using the upper half of the hash, because the lower
+/// bits are what the shard's `HashSet` uses to pick buckets. Choosing the
+/// shard from the lower bits would cause every value in a shard to share the
+/// same low bits, leading to excessive collisions.
+fn shard_for<B, S>(hasher: &S, value: &B, shards: usize) -> usize
+where
+    B: Hash + ?Sized,
+    S: BuildHasher,
+{
+    #[allow(clippy::cast_possible_truncation)] // Only 32 bits remain after the shift.
+    let high_bits = (hasher.hash_one(value) >> 32) as usize;
+    high_bits % shards
+}
+
+impl<T, const N: usize> ShardedGlobalPool<T, RandomState, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+{
+    /// Returns a new instance using [`RandomState`] for the internal hashing.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_capacity_and_hasher_init(0, RandomState::new)
+    }
+
+    /// Returns a new instance named `name` using [`RandomState`] for the
+    /// internal hashing. See [`ShardedGlobalPool::with_name()`] for more
+    /// information.
+    #[must_use]
+    pub const fn named(name: &'static str) -> Self {
+        Self::new().with_name(name)
+    }
+}
+
+impl<T, const N: usize> Default for ShardedGlobalPool<T, RandomState, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, S, const N: usize> ShardedGlobalPool<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    // This constant is only used to initialize the shards array, which can't
+    // be done with a non-Copy value in a const fn otherwise.
+    #[allow(clippy::declare_interior_mutable_const)]
+    const UNINITIALIZED_SHARD: GlobalShardState<T, S, N> = GlobalShardState {
+        pool: Mutex::new(None),
+        releases: ReleaseQueue::new(),
+    };
+
+    /// Returns a new instance using the provided hasher.
+    pub const fn with_hasher(hasher: S) -> Self {
+        Self::with_capacity_and_hasher(0, hasher)
+    }
+
+    /// Returns a new instance using the function to load the hasher when the
+    /// pool is first used.
+    pub const fn with_hasher_init(init: fn() -> S) -> Self {
+        Self::with_capacity_and_hasher_init(0, init)
+    }
+
+    /// Returns a new instance using the provided hasher with enough capacity to
+    /// hold the requested number of items without reallocating.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is 0.
+    pub const fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        Self::from_parts(capacity, ShardHasher::Static(hasher))
+    }
+
+    /// Returns a new instance using the function to load the hasher when the
+    /// pool is first used. The returned instance has enough capacity to hold
+    /// the requested number of items without reallocating.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is 0.
+    pub const fn with_capacity_and_hasher_init(capacity: usize, init: fn() -> S) -> Self {
+        Self::from_parts(
+            capacity,
+            ShardHasher::Lazy {
+                init,
+                hasher: OnceLock::new(),
+            },
+        )
+    }
+
+    /// Returns this pool after setting the limits that are enforced when
+    /// inserting new values. See [`ShardedGlobalPool::set_limits()`] for more
+    /// information.
+    #[must_use]
+    pub const fn with_limits(mut self, limits: PoolLimits) -> Self {
+        self.limits = AtomicLimits::new(limits);
+        self
+    }
+
+    /// Returns this pool after naming it `name`.
+    ///
+    /// The name is included in this pool's [`Debug`] output, and in its
+    /// `tracing` events when the `tracing` feature is enabled.
+    #[must_use]
+    pub const fn with_name(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Returns the name given to this pool using
+    /// [`ShardedGlobalPool::with_name()`].
+    #[must_use]
+    pub const fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    /// Adds this pool to the pools listed by
+    /// [`registry::pools()`](crate::registry::pools). Registering a pool more
+    /// than once has no effect.
+    ///
+    /// Listing a pool initializes each of its shards.
+    #[cfg(feature = "registry")]
+    pub fn register(&'static self)
+    where
+        T::Boxed: Send + Sync,
+        S: Send + Sync,
+    {
+        if !self.registered.swap(true, Ordering::Relaxed) {
+            registry::register_global(Arc::new(self));
+        }
+    }
+
+    const fn from_parts(capacity: usize, hasher: ShardHasher<S>) -> Self {
+        assert!(N > 0, "sharded pools require at least one shard");
+        Self {
+            hasher,
+            capacity,
+            shards: [Self::UNINITIALIZED_SHARD; N],
+            observers: Observers::new(),
+            name: None,
+            limits: AtomicLimits::new(PoolLimits::new()),
+            totals: ShardTotals::new(),
+            #[cfg(feature = "registry")]
+            registered: AtomicBool::new(false),
+        }
+    }
+
+    fn shard(&'static self, value: &T::Borrowed) -> GlobalShard<T, S, N> {
+        GlobalShard {
+            pool: self,
+            index: shard_for(self.hasher.get(), value, N),
+        }
+    }
+
+    fn shards(&'static self) -> impl Iterator<Item = GlobalShard<T, S, N>> {
+        (0..N).map(move |index| GlobalShard { pool: self, index })
+    }
+
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, a new [`Pooled`] value is created and returned.
+    ///
+    /// While any copies of the returned [`Pooled`] value are still allocated,
+    /// calling this function is guaranteed to return a copy of the same value.
+    /// Only the shard that the value belongs to is locked.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if inserting a new value would exceed this pool's
+    /// [limits](PoolLimits). [`ShardedGlobalPool::get_checked()`] returns an error
+    /// instead.
+    pub fn get<'a, V>(&'static self, value: V) -> Pooled<GlobalShard<T, S, N>, S>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        let value = value.into();
+        let shard = self.shard(&value);
+        shard.with_active_symbols(|symbols| symbols.get(value, &shard))
+    }
+
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, a new [`Pooled`] value is created and returned.
+    ///
+    /// This function behaves like [`ShardedGlobalPool::get()`], except that it
+    /// returns an error instead of panicking. Like `get()`, it waits for the
+    /// shard's lock if another thread holds it.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OutOfMemory`]: The shard could not grow its tables to hold
+    ///   `value`.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting `value` would exceed this pool's
+    ///   [limits](PoolLimits).
+    pub fn get_checked<'a, V>(
+        &'static self,
+        value: V,
+    ) -> Result<Pooled<GlobalShard<T, S, N>, S>, Error>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        let value = value.into();
+        let shard = self.shard(&value);
+        shard.with_active_symbols(|symbols| symbols.try_get(value, &shard))
+    }
+
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, a new [`Pooled`] value is created and returned.
+    ///
+    /// This function behaves like [`ShardedGlobalPool::get_checked()`], except that
+    /// it never waits for the shard's lock.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::WouldBlock`]: The value's shard is currently locked.
+    /// - [`Error::OutOfMemory`]: The shard could not grow its tables to hold
+    ///   `value`.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting `value` would exceed this pool's
+    ///   [limits](PoolLimits).
+    pub fn try_get<'a, V>(&'static self, value: V) -> Result<Pooled<GlobalShard<T, S, N>, S>, Error>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        let value = value.into();
+        let shard = self.shard(&value);
+        shard
+            .try_with_active_symbols(|symbols| symbols.try_get(value, &shard))
+            .ok_or(Error::WouldBlock)?
+    }
+
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, `None` is returned.
+    #[must_use]
+    pub fn get_existing(
+        &'static self,
+        value: &T::Borrowed,
+    ) -> Option<Pooled<GlobalShard<T, S, N>, S>> {
+        self.shard(value)
+            .with_active_symbols(|symbols| symbols.get_existing(value))
+    }
+
+    /// Returns true if `value` is currently stored in this pool.
+    #[must_use]
+    pub fn contains(&'static self, value: &T::Borrowed) -> bool {
+        self.shard(value)
+            .with_active_symbols(|symbols| symbols.contains(value))
+    }
+
+    /// Returns the backtraces captured when each live [`Pooled`] copy of
+    /// `value` was created or cloned, in the order they were captured.
+    ///
+    /// The pool's own references to `value` are not included. If `value` is
+    /// not stored in this pool, an empty list is returned.
+    #[cfg(feature = "track-holders")]
+    #[must_use]
+    pub fn holders_of(&'static self, value: &T::Borrowed) -> Vec<Arc<Backtrace>> {
+        self.shard(value)
+            .with_active_symbols(|symbols| symbols.holders_of(value))
+    }
+
+    /// Looks up `value` in this pool, returning an [`Entry`] that can be used
+    /// to decide whether to insert the value.
+    ///
+    /// The value's shard is not locked while the returned [`Entry`] is held.
+    /// If another thread inserts the same value before
+    /// [`VacantEntry::insert()`] is called, the existing value will be
+    /// returned by `insert()`.
+    pub fn entry<'a, V>(&'static self, value: V) -> Entry<'a, GlobalShard<T, S, N>, S>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        let value = value.into();
+        let shard = self.shard(&value);
+        match shard.with_active_symbols(|symbols| symbols.get_existing(&value)) {
+            Some(pooled) => Entry::Occupied(pooled),
+            None => Entry::Vacant(VacantEntry::new(shard, value)),
+        }
+    }
+
+    /// Returns the [`Pooled`] value that `symbol` refers to, or `None` if no
+    /// value is stored in the symbol's slot.
+    ///
+    /// Only the shard recorded in `symbol` is locked. If `symbol` was
+    /// retrieved using [`Pooled::symbol()`] and all copies of that value have
+    /// since been dropped, this function may return `None` or an unrelated
+    /// value that has reused the same slot.
+    #[must_use]
+    pub fn resolve(&'static self, symbol: Symbol) -> Option<Pooled<GlobalShard<T, S, N>, S>> {
+        let index = symbol.index();
+        let shard = GlobalShard {
+            pool: self,
+            index: index % N,
+        };
+        shard.with_active_symbols(|symbols| symbols.resolve(index / N))
+    }
+
+    /// Returns a copy of the [`Pooled`] value for `value`, inserting it into
+    /// the pool if needed, and marks it as permanent.
+    ///
+    /// Permanent values are never removed from the pool, even after all
+    /// copies of the returned [`Pooled`] value are dropped.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if inserting a new value would exceed this pool's
+    /// [limits](PoolLimits). [`ShardedGlobalPool::try_make_permanent()`] returns an
+    /// error instead.
+    pub fn make_permanent<'a, V>(&'static self, value: V) -> Pooled<GlobalShard<T, S, N>, S>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        match self.try_make_permanent(value) {
+            Ok(pooled) => pooled,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Returns a copy of the [`Pooled`] value for `value`, inserting it into
+    /// the pool if needed, and marks it as permanent.
+    ///
+    /// This function behaves like [`ShardedGlobalPool::make_permanent()`], except
+    /// that it returns an error instead of panicking. It waits for the
+    /// shard's lock if another thread holds it.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OutOfMemory`]: The shard could not grow its tables to hold
+    ///   `value`.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting `value` would exceed this pool's
+    ///   [limits](PoolLimits).
+    pub fn try_make_permanent<'a, V>(
+        &'static self,
+        value: V,
+    ) -> Result<Pooled<GlobalShard<T, S, N>, S>, Error>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        let value = value.into();
+        let shard = self.shard(&value);
+        shard.with_active_symbols(|symbols| {
+            let pooled = symbols.try_get(value, &shard)?;
+            symbols.make_permanent(&pooled);
+            Ok(pooled)
+        })
+    }
+
+    /// Returns a [`Symbol`] for `value`, inserting it into the pool if needed.
+    ///
+    /// The value is made permanent using [`ShardedGlobalPool::make_permanent()`],
+    /// which ensures the returned symbol can always be resolved using
+    /// [`ShardedGlobalPool::resolve()`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if inserting a new value would exceed this pool's
+    /// [limits](PoolLimits), or if the value's index does not fit in a
+    /// [`Symbol`]. [`ShardedGlobalPool::try_symbol()`] returns an error instead.
+    #[must_use]
+    pub fn symbol<'a, V>(&'static self, value: V) -> Symbol
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        match self.try_symbol(value) {
+            Ok(symbol) => symbol,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Returns a [`Symbol`] for `value`, inserting it into the pool if needed.
+    ///
+    /// This function behaves like [`ShardedGlobalPool::symbol()`], except that it
+    /// returns an error instead of panicking. It waits for the shard's lock if
+    /// another thread holds it.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OutOfMemory`]: The shard could not grow its tables to hold
+    ///   `value`.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting `value` would exceed this pool's
+    ///   [limits](PoolLimits).
+    /// - [`Error::SymbolOverflow`]: The value's index does not fit in a
+    ///   [`Symbol`].
+    pub fn try_symbol<'a, V>(&'static self, value: V) -> Result<Symbol, Error>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        self.try_make_permanent(value)?.try_symbol()
+    }
+
+    /// Returns a collection of the currently pooled items.
+    ///
+    /// Each shard is locked in turn while its values are collected.
+    #[must_use]
+    pub fn pooled<C>(&'static self) -> C
+    where
+        C: FromIterator<Pooled<GlobalShard<T, S, N>, S>>,
+    {
+        self.shards()
+            .flat_map(|shard| {
+                shard.with_active_symbols(|pool| {
+                    pool.active
+                        .iter()
+                        .map(|data| Pooled::new(data.clone()))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the number of values currently stored in this pool.
+    ///
+    /// Each shard is locked in turn while counting, so values inserted or
+    /// released concurrently may or may not be counted.
+    #[must_use]
+    pub fn len(&'static self) -> usize {
+        self.shards()
+            .map(|shard| shard.with_active_symbols(|symbols| symbols.len()))
+            .sum()
+    }
+
+    /// Returns true if this pool currently contains no values.
+    #[must_use]
+    pub fn is_empty(&'static self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an estimate of the memory used by this pool, and the memory
+    /// saved by sharing its values.
+    #[must_use]
+    pub fn memory_usage(&'static self) -> MemoryUsage {
+        self.shards()
+            .map(|shard| shard.with_active_symbols(|symbols| symbols.memory_usage()))
+            .fold(MemoryUsage::default(), MemoryUsage::merge)
+    }
+
+    /// Returns statistics about how this pool has been used since it was
+    /// created.
+    ///
+    /// The statistics of each shard are combined, and
+    /// [`peak_entries`](PoolStats::peak_entries) is the largest number of
+    /// values the pool has contained across all of its shards at once.
+    #[cfg(feature = "stats")]
+    #[must_use]
+    pub fn stats(&'static self) -> PoolStats {
+        let mut stats = self
+            .shards()
+            .map(|shard| shard.with_active_symbols(|symbols| symbols.stats))
+            .fold(PoolStats::default(), PoolStats::merge);
+        stats.peak_entries = self.totals.peak();
+        stats
+    }
+
+    /// Returns the limits that are enforced when inserting new values into
+    /// this pool.
+    #[must_use]
+    pub fn limits(&self) -> PoolLimits {
+        self.limits.get()
+    }
+
+    /// Sets the limits that are enforced when inserting new values into this
+    /// pool. Fallible functions such as [`ShardedGlobalPool::get_checked()`]
+    /// return an error when a limit would be exceeded, while functions such as
+    /// [`ShardedGlobalPool::get()`] panic.
+    ///
+    /// Limits apply to the values stored across all of the pool's shards.
+    /// Values already in the pool are not affected when limits are lowered.
+    pub fn set_limits(&self, limits: PoolLimits) {
+        self.limits.set(limits);
+    }
+
+    /// Registers `observer` to be invoked each time a value is inserted into
+    /// or freed from any shard of this pool.
+    ///
+    /// Observers are invoked after the shard is unlocked, so they can use the
+    /// pool without deadlocking. See [`PoolEvent`] for more information.
+    pub fn add_observer<F>(&'static self, observer: F)
+    where
+        F: Fn(&PoolEvent<'_, GlobalShard<T, S, N>, S>) + Send + Sync + 'static,
+    {
+        self.observers.add(Arc::new(observer));
+    }
+
+    /// Removes all observers registered with
+    /// [`ShardedGlobalPool::add_observer()`].
+    pub fn clear_observers(&self) {
+        self.observers.clear();
+    }
+
+    /// Returns the [`ReleaseMode`] this pool uses when values are no longer
+    /// referenced.
+    #[must_use]
+    pub fn release_mode(&self) -> ReleaseMode {
+        self.shards[0].releases.mode()
+    }
+
+    /// Sets the [`ReleaseMode`] this pool uses when values are no longer
+    /// referenced.
+    ///
+    /// Each shard queues its own releases, so a deferred threshold applies to
+    /// each shard separately. Values that are queued when switching to
+    /// [`ReleaseMode::Immediate`] are removed the next time their shard
+    /// retrieves a value or the pool is collected.
+    pub fn set_release_mode(&self, mode: ReleaseMode) {
+        for shard in &self.shards {
+            shard.releases.set_mode(mode);
+        }
+    }
+
+    /// Removes all values whose release was deferred and that are still
+    /// unreferenced, returning the number of values removed.
+    ///
+    /// Each shard is locked in turn while its queued values are removed. This
+    /// only has an effect when using [`ReleaseMode::Deferred`].
+    #[allow(clippy::must_use_candidate)] // The number of values removed is informational.
+    pub fn collect(&'static self) -> usize {
+        self.shards()
+            .map(|shard| {
+                shard.with_active_symbols(|symbols| symbols.collect(shard.release_queue()))
+            })
+            .sum()
+    }
+}
+
+impl<T, S, const N: usize> Debug for ShardedGlobalPool<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShardedGlobalPool")
+            .field("name", &self.name)
+            .field("shards", &N)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A handle to one shard of a [`ShardedGlobalPool`].
+pub struct GlobalShard<T, S, const N: usize>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd + 'static,
+    S: BuildHasher + Clone + 'static,
+{
+    pool: &'static ShardedGlobalPool<T, S, N>,
+    index: usize,
+}
+
+impl<T, S, const N: usize> Clone for GlobalShard<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            pool: self.pool,
+            index: self.index,
+        }
+    }
+}
+
+impl<T, S, const N: usize> PartialEq for GlobalShard<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.pool, other.pool) && self.index == other.index
+    }
+}
+
+impl<T, S, const N: usize> GlobalShard<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    /// Returns the pool stored in `shard`, creating it if this shard hasn't
+    /// been used yet.
+    fn initialized<'a>(&self, shard: &'a mut Option<Pool<Self, S>>) -> &'a mut Pool<Self, S> {
+        shard.get_or_insert_with(|| {
+            Pool::with_capacity_and_hasher(self.pool.capacity / N, self.pool.hasher.get().clone())
+        })
+    }
+}
+
+impl<T, S, const N: usize> Debug for GlobalShard<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GlobalShard")
+            .field("pool", &std::ptr::addr_of!(*self.pool))
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+#[cfg(feature = "registry")]
+impl<T, S, const N: usize> Registered for &'static ShardedGlobalPool<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    T::Boxed: Send + Sync,
+    S: BuildHasher + Clone + Send + Sync,
+{
+    fn info(self: Arc<Self>) -> PoolInfo {
+        let (len, memory) = self
+            .shards()
+            .map(|shard| {
+                shard.with_active_symbols(|symbols| (symbols.len(), symbols.memory_usage()))
+            })
+            .fold(
+                (0, MemoryUsage::default()),
+                |(len, memory), (shard_len, shard_memory)| {
+                    (len + shard_len, memory.merge(shard_memory))
+                },
+            );
+        PoolInfo {
+            name: self.name,
+            kind: Kind::ShardedGlobal,
+            value_type: std::any::type_name::<T>(),
+            len,
+            memory,
+        }
+    }
+}
+
+impl<T, S, const N: usize> PoolKind<S> for GlobalShard<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+}
+
+impl<T, S, const N: usize> PoolKindSealed<S> for GlobalShard<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    type Owned = T;
+    type Pooled = T::Boxed;
+
+    fn with_active_symbols<R>(&self, logic: impl FnOnce(&mut Pool<Self, S>) -> R) -> R {
+        let shard = pool::lock(self, &self.pool.shards[self.index].pool);
+        pool::with_locked(self, shard, |shard| self.initialized(shard), logic)
+    }
+
+    fn try_with_active_symbols<R>(&self, logic: impl FnOnce(&mut Pool<Self, S>) -> R) -> Option<R> {
+        let shard = match self.pool.shards[self.index].pool.try_lock() {
+            Ok(shard) => shard,
+            Err(TryLockError::WouldBlock) => return None,
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        };
+        Some(pool::with_locked(
+            self,
+            shard,
+            |shard| self.initialized(shard),
+            logic,
+        ))
+    }
+
+    fn enforced_limits(&self) -> Option<&AtomicLimits> {
+        Some(&self.pool.limits)
+    }
+
+    fn release_queue(&self) -> &ReleaseQueue<Self, S> {
+        &self.pool.shards[self.index].releases
+    }
+
+    fn observers(&self) -> Option<&Observers<Self, S>> {
+        Some(&self.pool.observers)
+    }
+
+    fn pool_name(&self) -> Option<&'static str> {
+        self.pool.name
+    }
+
+    fn shard_totals(&self) -> Option<&ShardTotals> {
+        Some(&self.pool.totals)
+    }
+
+    fn symbol_index(&self, index: usize) -> Option<usize> {
+        index.checked_mul(N)?.checked_add(self.index)
+    }
+
+    fn clone_for_value(&self) -> Self {
+        self.clone()
+    }
+
+    fn address_of(&self) -> *const () {
+        std::ptr::addr_of!(self.pool.shards[self.index]).cast()
+    }
+}
+
+/// A shared interning pool that is split into `N` independently locked
+/// shards.
+///
+/// This is the [`SharedPool`](crate::shared::SharedPool) equivalent of
+/// [`ShardedGlobalPool`], and it supports the same features.
+///
+/// ```rust
+/// use interner::sharded::{ShardedSharedPool, ShardedSharedString};
+///
+/// let pool = ShardedSharedPool::<String>::default();
+///
+/// let interned = pool.get(String::from("hello"));
+/// let second = pool.get("hello");
+///
+/// assert!(ShardedSharedString::ptr_eq(&interned, &second));
+/// ```
+pub struct ShardedSharedPool<T, S = RandomState, const N: usize = DEFAULT_SHARDS>(
+    Arc<SharedShards<T, S, N>>,
+    Handle,
+)
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone;
+
+struct SharedShards<T, S, const N: usize>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    hasher: S,
+    shards: [SharedShardState<T, S, N>; N],
+    observers: Observers<SharedShard<T, S, N>, S>,
+    name: Option<&'static str>,
+    /// The number of [`Handle::User`] handles to this pool. Each value in the
+    /// pool holds a handle as well, so the pool is only dropped once this
+    /// reaches zero and all of its values have been released.
+    handles: AtomicUsize,
+    limits: AtomicLimits,
+    totals: ShardTotals,
+    /// Whether this pool has been added to the registry.
+    #[cfg(feature = "registry")]
+    registered: AtomicBool,
+}
+
+/// A shard of a [`ShardedSharedPool`].
+struct SharedShardState<T, S, const N: usize>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    pool: Mutex<Pool<SharedShard<T, S, N>, S>>,
+    releases: ReleaseQueue<SharedShard<T, S, N>, S>,
+}
+
+impl<T, S, const N: usize> ShardedSharedPool<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    /// Creates a new pool using the provided [`BuildHasher`] for hashing
+    /// values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is 0.
+    #[must_use]
+    pub fn with_hasher(hasher: S) -> Self {
+        Self::with_capacity_and_hasher(0, hasher)
+    }
+
+    /// Creates a new pool using the provided [`BuildHasher`] for hashing
+    /// values. The pool will have enough capacity to allow inserting
+    /// `initial_capacity` pooled entries without reallocation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is 0.
+    #[must_use]
+    pub fn with_capacity_and_hasher(initial_capacity: usize, hasher: S) -> Self {
+        Self::from_parts(initial_capacity, hasher, None)
+    }
+
+    /// Creates a new pool named `name` using the provided [`BuildHasher`] for
+    /// hashing values. The pool will have enough capacity to allow inserting
+    /// `initial_capacity` pooled entries without reallocation.
+    ///
+    /// The name is included in this pool's [`Debug`] output, and in its
+    /// `tracing` events when the `tracing` feature is enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is 0.
+    #[must_use]
+    pub fn with_capacity_hasher_and_name(
+        initial_capacity: usize,
+        hasher: S,
+        name: &'static str,
+    ) -> Self {
+        Self::from_parts(initial_capacity, hasher, Some(name))
+    }
+
+    fn from_parts(initial_capacity: usize, hasher: S, name: Option<&'static str>) -> Self {
+        assert!(N > 0, "sharded pools require at least one shard");
+        let shards = std::array::from_fn(|_| SharedShardState {
+            pool: Mutex::new(Pool::with_capacity_and_hasher(
+                initial_capacity / N,
+                hasher.clone(),
+            )),
+            releases: ReleaseQueue::new(),
+        });
+        Self(
+            Arc::new(SharedShards {
+                hasher,
+                shards,
+                observers: Observers::new(),
+                name,
+                handles: AtomicUsize::new(1),
+                limits: AtomicLimits::new(PoolLimits::new()),
+                totals: ShardTotals::new(),
+                #[cfg(feature = "registry")]
+                registered: AtomicBool::new(false),
+            }),
+            Handle::User,
+        )
+    }
+
+    /// Returns this pool after setting the limits that are enforced when
+    /// inserting new values. See [`ShardedSharedPool::set_limits()`] for more
+    /// information.
+    #[must_use]
+    pub fn with_limits(self, limits: PoolLimits) -> Self {
+        self.set_limits(limits);
+        self
+    }
+
+    /// Returns a handle to this pool that is not counted as a user of the
+    /// pool.
+    fn internal(&self) -> Self {
+        Self(self.0.clone(), Handle::Internal)
+    }
+
+    fn shard(&self, value: &T::Borrowed) -> SharedShard<T, S, N> {
+        SharedShard {
+            pool: self.internal(),
+            index: shard_for(&self.0.hasher, value, N),
+        }
+    }
+
+    fn shards(&self) -> impl Iterator<Item = SharedShard<T, S, N>> + '_ {
+        (0..N).map(|index| SharedShard {
+            pool: self.internal(),
+            index,
+        })
+    }
+
+    /// Returns the name this pool was created with, if any.
+    #[must_use]
+    pub fn name(&self) -> Option<&'static str> {
+        self.0.name
+    }
+
+    /// Adds this pool to the pools listed by
+    /// [`registry::pools()`](crate::registry::pools). The pool is listed until
+    /// it is dropped. Registering a pool more than once has no effect.
+    #[cfg(feature = "registry")]
+    pub fn register(&self)
+    where
+        T: 'static,
+        T::Boxed: Send + Sync,
+        S: Send + Sync + 'static,
+    {
+        if !self.0.registered.swap(true, Ordering::Relaxed) {
+            let shards: Arc<dyn Registered> = self.0.clone();
+            registry::register_shared(Arc::downgrade(&shards));
+        }
+    }
+
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, a new [`Pooled`] value is created and returned.
+    ///
+    /// While any copies of the returned [`Pooled`] value are still allocated,
+    /// calling this function is guaranteed to return a copy of the same value.
+    /// Only the shard that the value belongs to is locked.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if inserting a new value would exceed this pool's
+    /// [limits](PoolLimits). [`ShardedSharedPool::get_checked()`] returns an error
+    /// instead.
+    #[must_use]
+    pub fn get<'a, V>(&self, value: V) -> Pooled<SharedShard<T, S, N>, S>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        let value = value.into();
+        let shard = self.shard(&value);
+        shard.with_active_symbols(|symbols| symbols.get(value, &shard))
+    }
+
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, a new [`Pooled`] value is created and returned.
+    ///
+    /// This function behaves like [`ShardedSharedPool::get()`], except that it
+    /// returns an error instead of panicking. Like `get()`, it waits for the
+    /// shard's lock if another thread holds it.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OutOfMemory`]: The shard could not grow its tables to hold
+    ///   `value`.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting `value` would exceed this pool's
+    ///   [limits](PoolLimits).
+    pub fn get_checked<'a, V>(&self, value: V) -> Result<Pooled<SharedShard<T, S, N>, S>, Error>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        let value = value.into();
+        let shard = self.shard(&value);
+        shard.with_active_symbols(|symbols| symbols.try_get(value, &shard))
+    }
+
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, a new [`Pooled`] value is created and returned.
+    ///
+    /// This function behaves like [`ShardedSharedPool::get_checked()`], except that
+    /// it never waits for the shard's lock.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::WouldBlock`]: The value's shard is currently locked.
+    /// - [`Error::OutOfMemory`]: The shard could not grow its tables to hold
+    ///   `value`.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting `value` would exceed this pool's
+    ///   [limits](PoolLimits).
+    pub fn try_get<'a, V>(&self, value: V) -> Result<Pooled<SharedShard<T, S, N>, S>, Error>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        let value = value.into();
+        let shard = self.shard(&value);
+        shard
+            .try_with_active_symbols(|symbols| symbols.try_get(value, &shard))
+            .ok_or(Error::WouldBlock)?
+    }
+
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, `None` is returned.
+    #[must_use]
+    pub fn get_existing(&self, value: &T::Borrowed) -> Option<Pooled<SharedShard<T, S, N>, S>> {
+        self.shard(value)
+            .with_active_symbols(|symbols| symbols.get_existing(value))
+    }
+
+    /// Returns true if `value` is currently stored in this pool.
+    #[must_use]
+    pub fn contains(&self, value: &T::Borrowed) -> bool {
+        self.shard(value)
+            .with_active_symbols(|symbols| symbols.contains(value))
+    }
+
+    /// Returns the backtraces captured when each live [`Pooled`] copy of
+    /// `value` was created or cloned, in the order they were captured.
+    ///
+    /// The pool's own references to `value` are not included. If `value` is
+    /// not stored in this pool, an empty list is returned.
+    #[cfg(feature = "track-holders")]
+    #[must_use]
+    pub fn holders_of(&self, value: &T::Borrowed) -> Vec<Arc<Backtrace>> {
+        self.shard(value)
+            .with_active_symbols(|symbols| symbols.holders_of(value))
+    }
+
+    /// Looks up `value` in this pool, returning an [`Entry`] that can be used
+    /// to decide whether to insert the value.
+    ///
+    /// The value's shard is not locked while the returned [`Entry`] is held.
+    /// If another thread inserts the same value before
+    /// [`VacantEntry::insert()`] is called, the existing value will be
+    /// returned by `insert()`.
+    pub fn entry<'a, V>(&self, value: V) -> Entry<'a, SharedShard<T, S, N>, S>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        let value = value.into();
+        let shard = self.shard(&value);
+        match shard.with_active_symbols(|symbols| symbols.get_existing(&value)) {
+            Some(pooled) => Entry::Occupied(pooled),
+            None => Entry::Vacant(VacantEntry::new(shard, value)),
+        }
+    }
+
+    /// Returns the [`Pooled`] value that `symbol` refers to, or `None` if no
+    /// value is stored in the symbol's slot.
+    ///
+    /// Only the shard recorded in `symbol` is locked. If `symbol` was
+    /// retrieved using [`Pooled::symbol()`] and all copies of that value have
+    /// since been dropped, this function may return `None` or an unrelated
+    /// value that has reused the same slot.
+    #[must_use]
+    pub fn resolve(&self, symbol: Symbol) -> Option<Pooled<SharedShard<T, S, N>, S>> {
+        let index = symbol.index();
+        let shard = SharedShard {
+            pool: self.internal(),
+            index: index % N,
+        };
+        shard.with_active_symbols(|symbols| symbols.resolve(index / N))
+    }
+
+    /// Returns a copy of the [`Pooled`] value for `value`, inserting it into
+    /// the pool if needed, and marks it as permanent.
+    ///
+    /// Permanent values are never removed from the pool, even after all
+    /// copies of the returned [`Pooled`] value are dropped.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if inserting a new value would exceed this pool's
+    /// [limits](PoolLimits). [`ShardedSharedPool::try_make_permanent()`] returns an
+    /// error instead.
+    pub fn make_permanent<'a, V>(&self, value: V) -> Pooled<SharedShard<T, S, N>, S>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        match self.try_make_permanent(value) {
+            Ok(pooled) => pooled,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Returns a copy of the [`Pooled`] value for `value`, inserting it into
+    /// the pool if needed, and marks it as permanent.
+    ///
+    /// This function behaves like [`ShardedSharedPool::make_permanent()`], except
+    /// that it returns an error instead of panicking. It waits for the
+    /// shard's lock if another thread holds it.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OutOfMemory`]: The shard could not grow its tables to hold
+    ///   `value`.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting `value` would exceed this pool's
+    ///   [limits](PoolLimits).
+    pub fn try_make_permanent<'a, V>(
+        &self,
+        value: V,
+    ) -> Result<Pooled<SharedShard<T, S, N>, S>, Error>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        let value = value.into();
+        let shard = self.shard(&value);
+        shard.with_active_symbols(|symbols| {
+            let pooled = symbols.try_get(value, &shard)?;
+            symbols.make_permanent(&pooled);
+            Ok(pooled)
+        })
+    }
+
+    /// Returns a [`Symbol`] for `value`, inserting it into the pool if needed.
+    ///
+    /// The value is made permanent using [`ShardedSharedPool::make_permanent()`],
+    /// which ensures the returned symbol can always be resolved using
+    /// [`ShardedSharedPool::resolve()`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if inserting a new value would exceed this pool's
+    /// [limits](PoolLimits), or if the value's index does not fit in a
+    /// [`Symbol`]. [`ShardedSharedPool::try_symbol()`] returns an error instead.
+    #[must_use]
+    pub fn symbol<'a, V>(&self, value: V) -> Symbol
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        match self.try_symbol(value) {
+            Ok(symbol) => symbol,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Returns a [`Symbol`] for `value`, inserting it into the pool if needed.
+    ///
+    /// This function behaves like [`ShardedSharedPool::symbol()`], except that it
+    /// returns an error instead of panicking. It waits for the shard's lock if
+    /// another thread holds it.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OutOfMemory`]: The shard could not grow its tables to hold
+    ///   `value`.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting `value` would exceed this pool's
+    ///   [limits](PoolLimits).
+    /// - [`Error::SymbolOverflow`]: The value's index does not fit in a
+    ///   [`Symbol`].
+    pub fn try_symbol<'a, V>(&self, value: V) -> Result<Symbol, Error>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        self.try_make_permanent(value)?.try_symbol()
+    }
+
+    /// Returns a collection of the currently pooled items.
+    ///
+    /// Each shard is locked in turn while its values are collected.
+    #[must_use]
+    pub fn pooled<C>(&self) -> C
+    where
+        C: FromIterator<Pooled<SharedShard<T, S, N>, S>>,
+    {
+        self.shards()
+            .flat_map(|shard| {
+                shard.with_active_symbols(|pool| {
+                    pool.active
+                        .iter()
+                        .map(|data| Pooled::new(data.clone()))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the number of values currently stored in this pool.
+    ///
+    /// Each shard is locked in turn while counting, so values inserted or
+    /// released concurrently may or may not be counted.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.shards()
+            .map(|shard| shard.with_active_symbols(|symbols| symbols.len()))
+            .sum()
+    }
+
+    /// Returns true if this pool currently contains no values.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an estimate of the memory used by this pool, and the memory
+    /// saved by sharing its values.
+    #[must_use]
+    pub fn memory_usage(&self) -> MemoryUsage {
+        self.shards()
+            .map(|shard| shard.with_active_symbols(|symbols| symbols.memory_usage()))
+            .fold(MemoryUsage::default(), MemoryUsage::merge)
+    }
+
+    /// Returns statistics about how this pool has been used since it was
+    /// created.
+    ///
+    /// The statistics of each shard are combined, and
+    /// [`peak_entries`](PoolStats::peak_entries) is the largest number of
+    /// values the pool has contained across all of its shards at once.
+    #[cfg(feature = "stats")]
+    #[must_use]
+    pub fn stats(&self) -> PoolStats {
+        let mut stats = self
+            .shards()
+            .map(|shard| shard.with_active_symbols(|symbols| symbols.stats))
+            .fold(PoolStats::default(), PoolStats::merge);
+        stats.peak_entries = self.0.totals.peak();
+        stats
+    }
+
+    /// Returns the limits that are enforced when inserting new values into
+    /// this pool.
+    #[must_use]
+    pub fn limits(&self) -> PoolLimits {
+        self.0.limits.get()
+    }
+
+    /// Sets the limits that are enforced when inserting new values into this
+    /// pool. Fallible functions such as [`ShardedSharedPool::get_checked()`]
+    /// return an error when a limit would be exceeded, while functions such as
+    /// [`ShardedSharedPool::get()`] panic.
+    ///
+    /// Limits apply to the values stored across all of the pool's shards.
+    /// Values already in the pool are not affected when limits are lowered.
+    pub fn set_limits(&self, limits: PoolLimits) {
+        self.0.limits.set(limits);
+    }
+
+    /// Registers `observer` to be invoked each time a value is inserted into
+    /// or freed from any shard of this pool.
+    ///
+    /// Observers are invoked after the shard is unlocked, so they can use the
+    /// pool without deadlocking. See [`PoolEvent`] for more information.
+    pub fn add_observer<F>(&self, observer: F)
+    where
+        F: Fn(&PoolEvent<'_, SharedShard<T, S, N>, S>) + Send + Sync + 'static,
+    {
+        self.0.observers.add(Arc::new(observer));
+    }
+
+    /// Removes all observers registered with
+    /// [`ShardedSharedPool::add_observer()`].
+    pub fn clear_observers(&self) {
+        self.0.observers.clear();
+    }
+
+    /// Returns the [`ReleaseMode`] this pool uses when values are no longer
+    /// referenced.
+    #[must_use]
+    pub fn release_mode(&self) -> ReleaseMode {
+        self.0.shards[0].releases.mode()
+    }
+
+    /// Sets the [`ReleaseMode`] this pool uses when values are no longer
+    /// referenced.
+    ///
+    /// Each shard queues its own releases, so a deferred threshold applies to
+    /// each shard separately. Values that are queued when switching to
+    /// [`ReleaseMode::Immediate`] are removed the next time their shard
+    /// retrieves a value or the pool is collected.
+    pub fn set_release_mode(&self, mode: ReleaseMode) {
+        for shard in &self.0.shards {
+            shard.releases.set_mode(mode);
+        }
+    }
+
+    /// Removes all values whose release was deferred and that are still
+    /// unreferenced, returning the number of values removed.
+    ///
+    /// Each shard is locked in turn while its queued values are removed. This
+    /// only has an effect when using [`ReleaseMode::Deferred`]. Queued values
+    /// are also removed when the final handle to this pool is dropped, after
+    /// which the pool releases values immediately.
+    #[allow(clippy::must_use_candidate)] // The number of values removed is informational.
+    pub fn collect(&self) -> usize {
+        self.shards()
+            .map(|shard| {
+                shard.with_active_symbols(|symbols| symbols.collect(shard.release_queue()))
+            })
+            .sum()
+    }
+}
+
+impl<T, const N: usize> ShardedSharedPool<T, RandomState, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+{
+    /// Returns a new pool named `name` using [`RandomState`] for hashing
+    /// values. See [`ShardedSharedPool::with_capacity_hasher_and_name()`] for
+    /// more information.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is 0.
+    #[must_use]
+    pub fn named(name: &'static str) -> Self {
+        Self::with_capacity_hasher_and_name(0, RandomState::new(), name)
+    }
+}
+
+impl<T, const N: usize> Default for ShardedSharedPool<T, RandomState, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+{
+    fn default() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<T, S, const N: usize> Clone for ShardedSharedPool<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    fn clone(&self) -> Self {
+        self.0.handles.fetch_add(1, Ordering::Relaxed);
+        Self(self.0.clone(), Handle::User)
+    }
+}
+
+impl<T, S, const N: usize> Drop for ShardedSharedPool<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    fn drop(&mut self) {
+        if self.1 == Handle::User && self.0.handles.fetch_sub(1, Ordering::AcqRel) == 1 {
+            // Each queued value holds a handle to this pool, which would keep
+            // the pool and its values allocated forever if they were never
+            // collected.
+            for shard in self.shards() {
+                pool::stop_deferring(&shard);
+            }
+        }
+    }
+}
+
+impl<T, S, const N: usize> PartialEq for ShardedSharedPool<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T, S, const N: usize> Debug for ShardedSharedPool<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShardedSharedPool")
+            .field("name", &self.0.name)
+            .field("shards", &N)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A handle to one shard of a [`ShardedSharedPool`].
+pub struct SharedShard<T, S, const N: usize>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    pool: ShardedSharedPool<T, S, N>,
+    index: usize,
+}
+
+impl<T, S, const N: usize> Clone for SharedShard<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            pool: self.pool.internal(),
+            index: self.index,
+        }
+    }
+}
+
+impl<T, S, const N: usize> PartialEq for SharedShard<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.pool == other.pool && self.index == other.index
+    }
+}
+
+impl<T, S, const N: usize> Debug for SharedShard<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedShard")
+            .field("pool", &Arc::as_ptr(&self.pool.0))
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+#[cfg(feature = "registry")]
+impl<T, S, const N: usize> Registered for SharedShards<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    T::Boxed: Send + Sync,
+    S: BuildHasher + Clone + Send + Sync,
+{
+    fn info(self: Arc<Self>) -> PoolInfo {
+        let pool = ShardedSharedPool(self, Handle::Internal);
+        let (len, memory) = pool
+            .shards()
+            .map(|shard| {
+                shard.with_active_symbols(|symbols| (symbols.len(), symbols.memory_usage()))
+            })
+            .fold(
+                (0, MemoryUsage::default()),
+                |(len, memory), (shard_len, shard_memory)| {
+                    (len + shard_len, memory.merge(shard_memory))
+                },
+            );
+        PoolInfo {
+            name: pool.0.name,
+            kind: Kind::ShardedShared,
+            value_type: std::any::type_name::<T>(),
+            len,
+            memory,
+        }
+    }
+}
+
+impl<T, S, const N: usize> PoolKind<S> for SharedShard<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+}
+
+impl<T, S, const N: usize> PoolKindSealed<S> for SharedShard<T, S, N>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher + Clone,
+{
+    type Owned = T;
+    type Pooled = T::Boxed;
+
+    fn with_active_symbols<R>(&self, logic: impl FnOnce(&mut Pool<Self, S>) -> R) -> R {
+        let symbols = pool::lock(self, &self.pool.0.shards[self.index].pool);
+        pool::with_locked(self, symbols, |symbols| &mut **symbols, logic)
+    }
+
+    fn try_with_active_symbols<R>(&self, logic: impl FnOnce(&mut Pool<Self, S>) -> R) -> Option<R> {
+        let symbols = match self.pool.0.shards[self.index].pool.try_lock() {
+            Ok(symbols) => symbols,
+            Err(TryLockError::WouldBlock) => return None,
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        };
+        Some(pool::with_locked(
+            self,
+            symbols,
+            |symbols| &mut **symbols,
+            logic,
+        ))
+    }
+
+    fn enforced_limits(&self) -> Option<&AtomicLimits> {
+        Some(&self.pool.0.limits)
+    }
+
+    fn release_queue(&self) -> &ReleaseQueue<Self, S> {
+        &self.pool.0.shards[self.index].releases
+    }
+
+    fn observers(&self) -> Option<&Observers<Self, S>> {
+        Some(&self.pool.0.observers)
+    }
+
+    fn pool_name(&self) -> Option<&'static str> {
+        self.pool.0.name
+    }
+
+    fn shard_totals(&self) -> Option<&ShardTotals> {
+        Some(&self.pool.0.totals)
+    }
+
+    fn symbol_index(&self, index: usize) -> Option<usize> {
+        index.checked_mul(N)?.checked_add(self.index)
+    }
+
+    fn clone_for_value(&self) -> Self {
+        self.clone()
+    }
+
+    fn address_of(&self) -> *const () {
+        std::ptr::addr_of!(self.pool.0.shards[self.index]).cast()
+    }
+}
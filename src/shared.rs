@@ -1,18 +1,38 @@
+#[cfg(feature = "track-holders")]
+use std::backtrace::Backtrace;
 use std::borrow::Cow;
 use std::collections::hash_map::RandomState;
+use std::ffi::{CString, OsString};
 use std::fmt::Debug;
 use std::hash::{BuildHasher, Hash};
-use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::path::PathBuf;
+#[cfg(feature = "registry")]
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, TryLockError};
 
 use crate::global::GlobalPool;
-use crate::pool::{Pool, PoolKindSealed, Poolable};
-use crate::{PoolKind, Pooled};
+#[cfg(feature = "stats")]
+use crate::pool::PoolStats;
+use crate::pool::{
+    self, AtomicLimits, MemoryUsage, Observers, Pool, PoolEvent, PoolKindSealed, ReleaseQueue,
+    ShardTotals,
+};
+#[cfg(feature = "registry")]
+use crate::registry::{self, Kind, PoolInfo, Registered};
+use crate::{
+    Entry, Error, FrozenPool, PoolKind, PoolLimits, PoolSnapshot, Poolable, Pooled, ReleaseMode,
+    Symbol, VacantEntry,
+};
 
 /// A pooled string that belongs to a [`StringPool`].
 pub type SharedString<S = RandomState> = Pooled<SharedPool<String, S>, S>;
 /// A pooled path that belongs to a [`PathPool`].
 pub type SharedPath<S = RandomState> = Pooled<SharedPool<PathBuf, S>, S>;
+/// A pooled OS string that belongs to an [`OsStringPool`].
+pub type SharedOsString<S = RandomState> = Pooled<SharedPool<OsString, S>, S>;
+/// A pooled C string that belongs to a [`CStringPool`].
+pub type SharedCString<S = RandomState> = Pooled<SharedPool<CString, S>, S>;
 /// A pooled buffer that belongs to a [`BufferPool`].
 pub type SharedBuffer<S = RandomState> = Pooled<SharedPool<Vec<u8>, S>, S>;
 
@@ -26,6 +46,17 @@ pub type StringPool<S = RandomState> = SharedPool<String, S>;
 /// Each [`PathPool`] has its own storage. When comparing [`SharedPath`]s
 /// from separate pools, the full string comparison function must be used.
 pub type PathPool<S = RandomState> = SharedPool<PathBuf, S>;
+/// An OS string interning pool that manages [`SharedOsString`]s.
+///
+/// Each [`OsStringPool`] has its own storage. When comparing
+/// [`SharedOsString`]s from separate pools, the full string comparison
+/// function must be used.
+pub type OsStringPool<S = RandomState> = SharedPool<OsString, S>;
+/// A C string interning pool that manages [`SharedCString`]s.
+///
+/// Each [`CStringPool`] has its own storage. When comparing [`SharedCString`]s
+/// from separate pools, the full string comparison function must be used.
+pub type CStringPool<S = RandomState> = SharedPool<CString, S>;
 /// A path interning pool that manages [`SharedBuffer`]s.
 ///
 /// Each [`BufferPool`] has its own storage. When comparing [`SharedBuffer`]s
@@ -35,24 +66,61 @@ pub type BufferPool<S = RandomState> = SharedPool<Vec<u8>, S>;
 /// A shared pool of values that ensures only one copy of any given value exists
 /// at any time.
 ///
-/// To retrieve a [`Pooled`] value, use [`SharedPool::get()`], which is
-/// implemented for these types:
+/// To retrieve a [`Pooled`] value, use [`SharedPool::get()`]. Pools can be
+/// created for any type that implements [`Poolable`], which includes these
+/// types:
 ///
 /// - [`String`]/[`&str`](str)
-/// - [`PathBuf`]/[`&Path`](Path)
+/// - [`PathBuf`]/[`&Path`](std::path::Path)
+/// - [`OsString`]/[`&OsStr`](std::ffi::OsStr)
+/// - [`CString`]/[`&CStr`](std::ffi::CStr)
 /// - [`Vec<u8>`]/`&[u8]`
-#[derive(Debug)]
-pub struct SharedPool<T, S = RandomState>(Arc<Mutex<Pool<Self, S>>>)
+pub struct SharedPool<T, S = RandomState>(Arc<SharedPoolData<T, S>>, Handle)
 where
     T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
     S: BuildHasher;
 
+/// Who holds a [`SharedPool`] or
+/// [`ShardedSharedPool`](crate::sharded::ShardedSharedPool) handle.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum Handle {
+    /// A handle held by a user of the pool, which is counted in the pool's
+    /// `handles`.
+    User,
+    /// A handle held by one of the pool's values, or used internally.
+    Internal,
+}
+
+#[derive(Debug)]
+struct SharedPoolData<T, S>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher,
+{
+    pool: Mutex<Pool<SharedPool<T, S>, S>>,
+    limits: AtomicLimits,
+    observers: Observers<SharedPool<T, S>, S>,
+    releases: ReleaseQueue<SharedPool<T, S>, S>,
+    name: Option<&'static str>,
+    /// The number of [`Handle::User`] handles to this pool. Each value in the
+    /// pool holds a handle as well, so the pool is only dropped once this
+    /// reaches zero and all of its values have been released.
+    handles: AtomicUsize,
+    /// Whether this pool has been added to the registry.
+    #[cfg(feature = "registry")]
+    registered: AtomicBool,
+}
+
 impl<T, S> SharedPool<T, S>
 where
     T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
     S: BuildHasher,
 {
     /// Returns a collection of the currently pooled items.
+    ///
+    /// Values that were made permanent can be identified using
+    /// [`Pooled::is_permanent()`]. To visit each value without creating
+    /// [`Pooled`] handles, use [`SharedPool::for_each()`] or [`SharedPool::fold()`].
     #[must_use]
     pub fn pooled<C>(&self) -> C
     where
@@ -61,16 +129,11 @@ where
         self.with_active_symbols(|pool| {
             pool.active
                 .iter()
-                .map(|data| Pooled(data.clone()))
+                .map(|data| Pooled::new(data.clone()))
                 .collect()
         })
     }
-}
 
-impl<S> SharedPool<String, S>
-where
-    S: BuildHasher,
-{
     /// Creates a new pool using the provided [`BuildHasher`] for hashing
     /// values.
     #[must_use]
@@ -83,62 +146,513 @@ where
     /// `initial_capacity` pooled entries without reallocation.
     #[must_use]
     pub fn with_capacity_and_hasher(initial_capacity: usize, hasher: S) -> Self {
-        Self(Arc::new(Mutex::new(Pool::with_capacity_and_hasher(
-            initial_capacity,
-            hasher,
-        ))))
+        Self::from_pool(
+            Pool::with_capacity_and_hasher(initial_capacity, hasher),
+            None,
+        )
+    }
+
+    /// Creates a new pool named `name` using the provided [`BuildHasher`] for
+    /// hashing values. The pool will have enough capacity to allow inserting
+    /// `initial_capacity` pooled entries without reallocation.
+    ///
+    /// The name is included in this pool's [`Debug`] output, and in its
+    /// `tracing` events when the `tracing` feature is enabled, and it is
+    /// reported by [`registry::pools()`](crate::registry::pools) once the pool
+    /// is registered using `SharedPool::register()`.
+    #[must_use]
+    pub fn with_capacity_hasher_and_name(
+        initial_capacity: usize,
+        hasher: S,
+        name: &'static str,
+    ) -> Self {
+        Self::from_pool(
+            Pool::with_capacity_and_hasher(initial_capacity, hasher),
+            Some(name),
+        )
+    }
+
+    /// Returns a new pool wrapping `pool`.
+    fn from_pool(pool: Pool<Self, S>, name: Option<&'static str>) -> Self {
+        Self(
+            Arc::new(SharedPoolData {
+                pool: Mutex::new(pool),
+                limits: AtomicLimits::new(PoolLimits::new()),
+                observers: Observers::new(),
+                releases: ReleaseQueue::new(),
+                name,
+                handles: AtomicUsize::new(1),
+                #[cfg(feature = "registry")]
+                registered: AtomicBool::new(false),
+            }),
+            Handle::User,
+        )
+    }
+
+    /// Returns this pool after setting the limits that are enforced when
+    /// inserting new values. See [`SharedPool::set_limits()`] for more
+    /// information.
+    #[must_use]
+    pub fn with_limits(self, limits: PoolLimits) -> Self {
+        self.set_limits(limits);
+        self
+    }
+
+    /// Returns the name this pool was created with, if any.
+    #[must_use]
+    pub fn name(&self) -> Option<&'static str> {
+        self.0.name
+    }
+
+    /// Adds this pool to the pools listed by
+    /// [`registry::pools()`](crate::registry::pools). The pool is listed until
+    /// it is dropped. Registering a pool more than once has no effect.
+    ///
+    /// ```rust
+    /// use interner::registry;
+    /// use interner::shared::StringPool;
+    ///
+    /// let pool = StringPool::named("words");
+    /// pool.register();
+    /// assert!(registry::pools().iter().any(|info| info.name == Some("words")));
+    ///
+    /// drop(pool);
+    /// assert!(!registry::pools().iter().any(|info| info.name == Some("words")));
+    /// ```
+    #[cfg(feature = "registry")]
+    pub fn register(&self)
+    where
+        T: 'static,
+        T::Boxed: Send + Sync,
+        S: Send + Sync + 'static,
+    {
+        if !self.0.registered.swap(true, Ordering::Relaxed) {
+            let data: Arc<dyn Registered> = self.0.clone();
+            registry::register_shared(Arc::downgrade(&data));
+        }
     }
 
-    /// Returns a copy of an existing [`SharedString`] if one is found.
-    /// Otherwise, a new [`SharedString`] is created and returned.
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, a new [`Pooled`] value is created and returned.
+    ///
+    /// While any copies of the returned [`Pooled`] value are still allocated,
+    /// calling this function is guaranteed to return a copy of the same value.
+    ///
+    /// For the built-in pool types, this accepts:
+    ///
+    /// - [`StringPool`]: [`String`] or [`&str`](str), returning a
+    ///   [`SharedString`].
+    /// - [`PathPool`]: [`PathBuf`] or [`&Path`](std::path::Path), returning a
+    ///   [`SharedPath`].
+    /// - [`OsStringPool`]: [`OsString`] or [`&OsStr`](std::ffi::OsStr),
+    ///   returning a [`SharedOsString`].
+    /// - [`CStringPool`]: [`CString`] or [`&CStr`](std::ffi::CStr), returning
+    ///   a [`SharedCString`].
+    /// - [`BufferPool`]: [`Vec<u8>`] or `&[u8]`, returning a [`SharedBuffer`].
     ///
-    /// While any copies of the returned [`SharedString`] are still allocated,
-    /// calling this function is guaranteed to return a copy of the same string.
+    /// # Panics
+    ///
+    /// This function panics if inserting a new value would exceed this pool's
+    /// [limits](PoolLimits). [`SharedPool::get_checked()`] returns an error
+    /// instead.
     #[must_use]
-    pub fn get<'a, V>(&self, value: V) -> SharedString<S>
+    pub fn get<'a, V>(&self, value: V) -> Pooled<Self, S>
     where
-        V: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
     {
         let value = value.into();
         self.with_active_symbols(|symbols| symbols.get(value, self))
     }
-}
 
-impl<S> SharedPool<PathBuf, S>
-where
-    S: BuildHasher,
-{
-    /// Returns a copy of an existing [`SharedPath`] if one is found. Otherwise,
-    /// a new [`SharedPath`] is created and returned.
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, a new [`Pooled`] value is created and returned.
+    ///
+    /// This function behaves like [`SharedPool::get()`], except that it returns an
+    /// error instead of panicking. Like `get()`, it waits for the pool's lock
+    /// if another thread holds it.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OutOfMemory`]: The pool could not grow its tables to hold
+    ///   `value`.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting `value` would exceed this pool's
+    ///   [limits](PoolLimits).
+    pub fn get_checked<'a, V>(&self, value: V) -> Result<Pooled<Self, S>, Error>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        let value = value.into();
+        self.with_active_symbols(|symbols| symbols.try_get(value, self))
+    }
+
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, a new [`Pooled`] value is created and returned.
+    ///
+    /// This function behaves like [`SharedPool::get_checked()`], except that it
+    /// never waits for the pool's lock.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::WouldBlock`]: The pool is currently locked.
+    /// - [`Error::OutOfMemory`]: The pool could not grow its tables to hold
+    ///   `value`.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting `value` would exceed this pool's
+    ///   [limits](PoolLimits).
+    pub fn try_get<'a, V>(&self, value: V) -> Result<Pooled<Self, S>, Error>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        let value = value.into();
+        self.try_with_active_symbols(|symbols| symbols.try_get(value, self))
+            .ok_or(Error::WouldBlock)?
+    }
+
+    /// Invokes `visitor` with a reference to each value currently stored in
+    /// this pool.
+    ///
+    /// Unlike [`SharedPool::pooled()`], no [`Pooled`] handles are created. The
+    /// pool is locked while `visitor` is invoked, so `visitor` must not access
+    /// this pool. Dropping [`Pooled`] values from this pool within `visitor` is
+    /// allowed.
+    pub fn for_each<F>(&self, mut visitor: F)
+    where
+        F: FnMut(&T::Boxed),
+    {
+        self.with_active_symbols(|symbols| symbols.values().for_each(&mut visitor));
+    }
+
+    /// Folds every value currently stored in this pool into an accumulator
+    /// using `visitor`, returning the final accumulator.
+    ///
+    /// Unlike [`SharedPool::pooled()`], no [`Pooled`] handles are created. The
+    /// pool is locked while `visitor` is invoked, so `visitor` must not access
+    /// this pool. Dropping [`Pooled`] values from this pool within `visitor` is
+    /// allowed.
+    pub fn fold<B, F>(&self, init: B, visitor: F) -> B
+    where
+        F: FnMut(B, &T::Boxed) -> B,
+    {
+        self.with_active_symbols(|symbols| symbols.values().fold(init, visitor))
+    }
+
+    /// Locks this pool and returns a snapshot that borrows each value
+    /// currently stored in it.
+    ///
+    /// Unlike [`SharedPool::pooled()`], no [`Pooled`] handles are created. The
+    /// pool stays locked until the snapshot is dropped, so this pool must not
+    /// be accessed from the current thread while the snapshot is alive.
+    /// Dropping [`Pooled`] values from this pool while the snapshot is alive is
+    /// allowed.
+    ///
+    /// ```rust
+    /// use interner::shared::StringPool;
+    ///
+    /// let pool = StringPool::default();
+    /// let hello = pool.get("hello");
+    /// let total: usize = pool.iter_snapshot().iter().map(|value| value.len()).sum();
+    /// assert_eq!(total, 5);
+    /// ```
+    #[must_use]
+    pub fn iter_snapshot(&self) -> PoolSnapshot<'_, Self, S> {
+        let symbols = pool::lock(self, &self.0.pool);
+        PoolSnapshot::new(self.clone_for_value(), symbols)
+    }
+
+    /// Returns an immutable snapshot of this pool's current contents, which
+    /// can be read without locking.
+    ///
+    /// The returned [`FrozenPool`] keeps all values that are currently in
+    /// this pool allocated until it is dropped.
+    #[must_use]
+    pub fn freeze(&self) -> FrozenPool<Self, S>
+    where
+        S: Clone,
+    {
+        self.with_active_symbols(|symbols| FrozenPool::new(self.clone(), symbols))
+    }
+
+    /// Returns the limits that are enforced when inserting new values into
+    /// this pool.
+    #[must_use]
+    pub fn limits(&self) -> PoolLimits {
+        self.0.limits.get()
+    }
+
+    /// Sets the limits that are enforced when inserting new values into this
+    /// pool. Fallible functions such as [`SharedPool::get_checked()`] return an
+    /// error when a limit would be exceeded, while functions such as
+    /// [`SharedPool::get()`] panic.
+    ///
+    /// Values already in the pool are not affected when limits are lowered.
+    pub fn set_limits(&self, limits: PoolLimits) {
+        self.0.limits.set(limits);
+    }
+
+    /// Registers `observer` to be invoked each time a value is inserted into
+    /// or freed from this pool.
+    ///
+    /// Observers are invoked after the pool is unlocked, so they can use the
+    /// pool without deadlocking. See [`PoolEvent`] for more information.
+    pub fn add_observer<F>(&self, observer: F)
+    where
+        F: Fn(&PoolEvent<'_, Self, S>) + Send + Sync + 'static,
+    {
+        self.0.observers.add(Arc::new(observer));
+    }
+
+    /// Removes all observers registered with [`SharedPool::add_observer()`].
+    pub fn clear_observers(&self) {
+        self.0.observers.clear();
+    }
+
+    /// Returns the [`ReleaseMode`] this pool uses when values are no longer
+    /// referenced.
+    #[must_use]
+    pub fn release_mode(&self) -> ReleaseMode {
+        self.0.releases.mode()
+    }
+
+    /// Sets the [`ReleaseMode`] this pool uses when values are no longer
+    /// referenced.
+    ///
+    /// Values that are queued when switching to [`ReleaseMode::Immediate`]
+    /// are removed the next time the pool retrieves a value or is collected.
+    pub fn set_release_mode(&self, mode: ReleaseMode) {
+        self.0.releases.set_mode(mode);
+    }
+
+    /// Removes all values whose release was deferred and that are still
+    /// unreferenced, returning the number of values removed.
+    ///
+    /// This only has an effect when using [`ReleaseMode::Deferred`]. Queued
+    /// values are also removed when the final handle to this pool is dropped,
+    /// after which the pool releases values immediately.
+    #[allow(clippy::must_use_candidate)] // The number of values removed is informational.
+    pub fn collect(&self) -> usize {
+        self.with_active_symbols(|symbols| symbols.collect(&self.0.releases))
+    }
+
+    /// Returns the number of values currently stored in this pool.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.with_active_symbols(|symbols| symbols.len())
+    }
+
+    /// Returns true if this pool currently contains no values.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an estimate of the memory used by this pool, and the memory
+    /// saved by sharing its values.
+    #[must_use]
+    pub fn memory_usage(&self) -> MemoryUsage {
+        self.with_active_symbols(|symbols| symbols.memory_usage())
+    }
+
+    /// Returns statistics about how this pool has been used since it was
+    /// created.
+    #[cfg(feature = "stats")]
+    #[must_use]
+    pub fn stats(&self) -> PoolStats {
+        self.with_active_symbols(|symbols| symbols.stats)
+    }
+
+    /// Returns the number of values this pool can hold without reallocating.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.with_active_symbols(|symbols| symbols.capacity())
+    }
+
+    /// Reserves capacity for at least `additional` more values to be inserted
+    /// without reallocating.
+    pub fn reserve(&self, additional: usize) {
+        self.with_active_symbols(|symbols| symbols.reserve(additional));
+    }
+
+    /// Shrinks the capacity of this pool as much as possible.
+    ///
+    /// Unused slots at the end of the pool's storage are released. Values
+    /// that are still pooled are never moved.
+    pub fn shrink_to_fit(&self) {
+        self.with_active_symbols(Pool::shrink_to_fit);
+    }
+
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, `None` is returned.
+    ///
+    /// Unlike [`SharedPool::get()`], this function never inserts `value` into the
+    /// pool. This allows checking untrusted input against the values already
+    /// in the pool without allowing the pool to grow.
+    #[must_use]
+    pub fn get_existing(&self, value: &T::Borrowed) -> Option<Pooled<Self, S>> {
+        self.with_active_symbols(|symbols| symbols.get_existing(value))
+    }
+
+    /// Returns a copy of an existing [`Pooled`] value if one is found.
+    /// Otherwise, `None` is returned.
+    ///
+    /// This function behaves like [`SharedPool::get_existing()`], except that it
+    /// never waits for the pool's lock.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::WouldBlock`] if the pool is currently locked.
+    pub fn try_get_existing(&self, value: &T::Borrowed) -> Result<Option<Pooled<Self, S>>, Error> {
+        self.try_with_active_symbols(|symbols| symbols.get_existing(value))
+            .ok_or(Error::WouldBlock)
+    }
+
+    /// Returns true if `value` is currently stored in this pool.
+    #[must_use]
+    pub fn contains(&self, value: &T::Borrowed) -> bool {
+        self.with_active_symbols(|symbols| symbols.contains(value))
+    }
+
+    /// Returns the backtraces captured when each live [`Pooled`] copy of
+    /// `value` was created or cloned, in the order they were captured.
     ///
-    /// While any copies of the returned [`SharedPath`] are still allocated,
-    /// calling this function is guaranteed to return a copy of the same path.
+    /// The pool's own references to `value` are not included. If `value` is
+    /// not stored in this pool, an empty list is returned.
+    #[cfg(feature = "track-holders")]
     #[must_use]
-    pub fn get<'a, V>(&self, value: V) -> SharedPath<S>
+    pub fn holders_of(&self, value: &T::Borrowed) -> Vec<Arc<Backtrace>> {
+        self.with_active_symbols(|symbols| symbols.holders_of(value))
+    }
+
+    /// Looks up `value` in this pool, returning an [`Entry`] that can be used
+    /// to decide whether to insert the value.
+    ///
+    /// The pool is not locked while the returned [`Entry`] is held. If another
+    /// thread inserts the same value before [`VacantEntry::insert()`] is
+    /// called, the existing value will be returned by `insert()`.
+    pub fn entry<'a, V>(&self, value: V) -> Entry<'a, Self, S>
     where
-        V: Into<Cow<'a, Path>>,
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
     {
         let value = value.into();
-        self.with_active_symbols(|symbols| symbols.get(value, self))
+        match self.get_existing(&value) {
+            Some(pooled) => Entry::Occupied(pooled),
+            None => Entry::Vacant(VacantEntry::new(self.clone(), value)),
+        }
     }
-}
 
-impl<S> SharedPool<Vec<u8>, S>
-where
-    S: BuildHasher,
-{
-    /// Returns a copy of an existing [`SharedBuffer`] if one is found. Otherwise,
-    /// a new [`SharedBuffer`] is created and returned.
+    /// Returns the [`Pooled`] value that `symbol` refers to, or `None` if no
+    /// value is stored in the symbol's slot.
     ///
-    /// While any copies of the returned [`SharedBuffer`] are still allocated,
-    /// calling this function is guaranteed to return a copy of the same buffer.
+    /// If `symbol` was retrieved using [`Pooled::symbol()`] and all copies of
+    /// that value have since been dropped, this function may return `None` or
+    /// an unrelated value that has reused the same slot.
     #[must_use]
-    pub fn get<'a, V>(&self, value: V) -> SharedBuffer<S>
+    pub fn resolve(&self, symbol: Symbol) -> Option<Pooled<Self, S>> {
+        self.with_active_symbols(|symbols| symbols.resolve(symbol.index()))
+    }
+
+    /// Returns a copy of the [`Pooled`] value for `value`, inserting it into
+    /// the pool if needed, and marks it as permanent.
+    ///
+    /// Permanent values are never removed from the pool, even after all
+    /// copies of the returned [`Pooled`] value are dropped. Because each
+    /// pooled value holds a reference to its pool, permanent values keep both
+    /// themselves and their pool allocated for the duration of the process.
+    ///
+    /// [`Pooled::is_permanent()`] can be used to check whether a value
+    /// returned from [`SharedPool::pooled()`] is permanent.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if inserting a new value would exceed this pool's
+    /// [limits](PoolLimits). [`SharedPool::try_make_permanent()`] returns an
+    /// error instead.
+    pub fn make_permanent<'a, V>(&self, value: V) -> Pooled<Self, S>
     where
-        V: Into<Cow<'a, [u8]>>,
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        match self.try_make_permanent(value) {
+            Ok(pooled) => pooled,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Returns a copy of the [`Pooled`] value for `value`, inserting it into
+    /// the pool if needed, and marks it as permanent.
+    ///
+    /// This function behaves like [`SharedPool::make_permanent()`], except that
+    /// it returns an error instead of panicking. It waits for the pool's lock
+    /// if another thread holds it.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OutOfMemory`]: The pool could not grow its tables to hold
+    ///   `value`.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting `value` would exceed this pool's
+    ///   [limits](PoolLimits).
+    pub fn try_make_permanent<'a, V>(&self, value: V) -> Result<Pooled<Self, S>, Error>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
     {
         let value = value.into();
-        self.with_active_symbols(|symbols| symbols.get(value, self))
+        self.with_active_symbols(|symbols| {
+            let pooled = symbols.try_get(value, self)?;
+            symbols.make_permanent(&pooled);
+            Ok(pooled)
+        })
+    }
+
+    /// Returns a [`Symbol`] for `value`, inserting it into the pool if needed.
+    ///
+    /// The value is made permanent using [`SharedPool::make_permanent()`],
+    /// which ensures the returned symbol can always be resolved using
+    /// [`SharedPool::resolve()`].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if inserting a new value would exceed this pool's
+    /// [limits](PoolLimits), or if the value's index does not fit in a
+    /// [`Symbol`]. [`SharedPool::try_symbol()`] returns an error instead.
+    #[must_use]
+    pub fn symbol<'a, V>(&self, value: V) -> Symbol
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        match self.try_symbol(value) {
+            Ok(symbol) => symbol,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Returns a [`Symbol`] for `value`, inserting it into the pool if needed.
+    ///
+    /// This function behaves like [`SharedPool::symbol()`], except that it returns
+    /// an error instead of panicking. It waits for the pool's lock if another
+    /// thread holds it.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OutOfMemory`]: The pool could not grow its tables to hold
+    ///   `value`.
+    /// - [`Error::TooManyEntries`], [`Error::TooManyBytes`], or
+    ///   [`Error::ValueTooLong`]: Inserting `value` would exceed this pool's
+    ///   [limits](PoolLimits).
+    /// - [`Error::SymbolOverflow`]: The value's index does not fit in a
+    ///   [`Symbol`].
+    pub fn try_symbol<'a, V>(&self, value: V) -> Result<Symbol, Error>
+    where
+        V: Into<Cow<'a, T::Borrowed>>,
+        T::Borrowed: 'a,
+    {
+        self.try_make_permanent(value)?.try_symbol()
     }
 }
 
@@ -148,7 +662,23 @@ where
     S: BuildHasher,
 {
     fn clone(&self) -> Self {
-        Self(self.0.clone())
+        self.0.handles.fetch_add(1, Ordering::Relaxed);
+        Self(self.0.clone(), Handle::User)
+    }
+}
+
+impl<T, S> Drop for SharedPool<T, S>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher,
+{
+    fn drop(&mut self) {
+        if self.1 == Handle::User && self.0.handles.fetch_sub(1, Ordering::AcqRel) == 1 {
+            // Each queued value holds a handle to this pool, which would keep
+            // the pool and its values allocated forever if they were never
+            // collected.
+            pool::stop_deferring(self);
+        }
     }
 }
 
@@ -168,9 +698,46 @@ where
     type Pooled = T::Boxed;
 
     fn with_active_symbols<R>(&self, logic: impl FnOnce(&mut Pool<Self, S>) -> R) -> R {
-        let mut symbols = self.0.lock().expect("poisoned");
+        let symbols = pool::lock(self, &self.0.pool);
+        pool::with_locked(self, symbols, |symbols| &mut **symbols, logic)
+    }
+
+    fn try_with_active_symbols<R>(&self, logic: impl FnOnce(&mut Pool<Self, S>) -> R) -> Option<R> {
+        let symbols = match self.0.pool.try_lock() {
+            Ok(symbols) => symbols,
+            Err(TryLockError::WouldBlock) => return None,
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        };
+        Some(pool::with_locked(
+            self,
+            symbols,
+            |symbols| &mut **symbols,
+            logic,
+        ))
+    }
+
+    fn enforced_limits(&self) -> Option<&AtomicLimits> {
+        Some(&self.0.limits)
+    }
+
+    fn release_queue(&self) -> &ReleaseQueue<Self, S> {
+        &self.0.releases
+    }
+
+    fn observers(&self) -> Option<&Observers<Self, S>> {
+        Some(&self.0.observers)
+    }
+
+    fn pool_name(&self) -> Option<&'static str> {
+        self.0.name
+    }
+
+    fn shard_totals(&self) -> Option<&ShardTotals> {
+        None
+    }
 
-        logic(&mut symbols)
+    fn clone_for_value(&self) -> Self {
+        Self(self.0.clone(), Handle::Internal)
     }
 
     fn address_of(&self) -> *const () {
@@ -210,11 +777,61 @@ where
     }
 }
 
+impl<T> SharedPool<T, RandomState>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+{
+    /// Returns a new pool named `name` using [`RandomState`] for hashing
+    /// values. See [`SharedPool::with_capacity_hasher_and_name()`] for more
+    /// information.
+    #[must_use]
+    pub fn named(name: &'static str) -> Self {
+        Self::with_capacity_hasher_and_name(0, RandomState::new(), name)
+    }
+}
+
+impl<T, S> Debug for SharedPool<T, S>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("SharedPool");
+        debug.field("name", &self.0.name);
+        // The pool is never waited for, as it may be locked by this thread.
+        if let Ok(pool) = self.0.pool.try_lock() {
+            debug.field("len", &pool.len());
+        }
+        debug.finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "registry")]
+impl<T, S> Registered for SharedPoolData<T, S>
+where
+    T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
+    T::Boxed: Send + Sync,
+    S: BuildHasher + Send + Sync,
+{
+    fn info(self: Arc<Self>) -> PoolInfo {
+        let pool = SharedPool(self, Handle::Internal);
+        let (len, memory) =
+            pool.with_active_symbols(|symbols| (symbols.len(), symbols.memory_usage()));
+        PoolInfo {
+            name: pool.0.name,
+            kind: Kind::Shared,
+            value_type: std::any::type_name::<T>(),
+            len,
+            memory,
+        }
+    }
+}
+
 impl<T> Default for SharedPool<T, RandomState>
 where
     T: Poolable + Debug + Clone + Eq + PartialEq + Hash + Ord + PartialOrd,
 {
     fn default() -> Self {
-        Self(Arc::default())
+        Self::from_pool(Pool::default(), None)
     }
 }
@@ -1,21 +1,30 @@
 use std::borrow::Cow;
 use std::collections::hash_map::RandomState;
 use std::collections::HashSet;
-use std::hash::{BuildHasher, Hasher};
+use std::ffi::{CString, OsStr, OsString};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::panic;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier};
 use std::thread;
 
 use crate::global::{
-    GlobalPool, GlobalString, StaticPooledBuffer, StaticPooledPath, StaticPooledString,
+    CStringPool, GlobalPool, GlobalString, StaticPooledBuffer, StaticPooledCString,
+    StaticPooledOsString, StaticPooledPath, StaticPooledString,
 };
 use crate::pool::PoolKindSealed;
-use crate::shared::{SharedPool, SharedString, StringPool};
-use crate::Pooled;
+use crate::sharded::{
+    ShardedGlobalOsString, ShardedGlobalPool, ShardedGlobalString, ShardedSharedCString,
+    ShardedSharedPool,
+};
+use crate::shared::{OsStringPool, SharedPool, SharedString, StringPool};
+use crate::{Entry, Error, PoolLimits, Poolable, Pooled, ReleaseMode, Symbol};
 
 static GLOBAL_STRINGS: GlobalPool<String> = GlobalPool::new();
 static GLOBAL_PATHS: GlobalPool<PathBuf> = GlobalPool::new();
 static GLOBAL_BUFFERS: GlobalPool<Vec<u8>> = GlobalPool::new();
+static GLOBAL_OS_STRINGS: GlobalPool<OsString> = GlobalPool::new();
 
 #[test]
 fn basics() {
@@ -29,14 +38,14 @@ fn basics() {
     drop(first_again);
     // Dropping the second copy shouldn't free the underlying symbol
     (&GLOBAL_STRINGS).with_active_symbols(|symbols| {
-        assert!(symbols.active.contains("basics-test-symbol"));
+        assert!(symbols.contains("basics-test-symbol"));
         assert!(!symbols.slots.is_empty());
         assert!(symbols.slots[slot].is_some());
-        assert!(!symbols.free_slots.iter().any(|free| *free == slot));
+        assert!(!symbols.free_slots.contains(&slot));
     });
     drop(first_symbol);
     (&GLOBAL_STRINGS).with_active_symbols(|symbols| {
-        assert!(!symbols.active.contains("basics-test-symbol"));
+        assert!(!symbols.contains("basics-test-symbol"));
         match &symbols.slots[slot] {
             Some(new_symbol) => {
                 // This test isn't run in isolation, so other symbols may get
@@ -45,7 +54,7 @@ fn basics() {
                 assert_ne!(new_symbol, "basics-test-symbol");
             }
             None => {
-                assert!(symbols.free_slots.iter().any(|free| *free == slot));
+                assert!(symbols.free_slots.contains(&slot));
             }
         }
     });
@@ -115,6 +124,7 @@ fn buffers() {
 }
 
 #[test]
+#[allow(clippy::mutable_key_type)] // Pooled hashes its index, which never changes.
 fn hashing() {
     let mut set = HashSet::new();
     let shared = StringPool::default();
@@ -124,6 +134,7 @@ fn hashing() {
 }
 
 #[test]
+#[allow(clippy::mutable_key_type)] // Pooled hashes its index, which never changes.
 fn with_hasher() {
     let mut set = HashSet::new();
     let shared = StringPool::with_hasher(RandomState::default());
@@ -150,7 +161,7 @@ fn multithreaded_reaquire() {
     }
     // The failure case for the code would end up not freing the string.
     (&GLOBAL_STRINGS).with_active_symbols(|symbols| {
-        assert!(!symbols.active.contains("multithreaded"));
+        assert!(!symbols.contains("multithreaded"));
     });
 }
 
@@ -248,3 +259,1591 @@ fn statics() {
     test_static!(STATIC_BUFFER, &b"static"[..]);
     test_static!(STATIC_BUFFER_LAZY, &b"static-lazy"[..]);
 }
+
+#[test]
+fn custom_poolable() {
+    #[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+    enum Token {
+        Ident(String),
+        Number(u64),
+    }
+
+    impl Poolable for Token {
+        type Borrowed = Self;
+        type Boxed = Self;
+
+        fn boxed(self) -> Self::Boxed {
+            self
+        }
+
+        fn byte_len(value: &Self) -> usize {
+            std::mem::size_of_val(value)
+                + match value {
+                    Token::Ident(ident) => ident.len(),
+                    Token::Number(_) => 0,
+                }
+        }
+    }
+
+    static GLOBAL_TOKENS: GlobalPool<Token> = GlobalPool::new();
+
+    let shared = SharedPool::<Token>::default();
+    let ident = Token::Ident(String::from("custom"));
+    let first = shared.get(Cow::Borrowed(&ident));
+    let second = shared.get(Cow::Owned(ident.clone()));
+    assert!(Pooled::ptr_eq(&first, &second));
+    assert_eq!(*first, ident);
+
+    let number = shared.get(Cow::Owned(Token::Number(42)));
+    assert!(!Pooled::ptr_eq(&first, &number));
+    assert_eq!(
+        shared.memory_usage().values,
+        2 * std::mem::size_of::<Token>() + "custom".len()
+    );
+
+    let global = GLOBAL_TOKENS.get(Cow::Borrowed(&ident));
+    assert_eq!(global, first);
+    assert!(!Pooled::ptr_eq(&global, &first));
+
+    drop((first, second, number));
+    let remaining: Vec<Pooled<SharedPool<Token>, RandomState>> = shared.pooled();
+    assert!(remaining.is_empty());
+}
+
+#[test]
+fn os_strings() {
+    static STATIC_OS_STRING: StaticPooledOsString =
+        GLOBAL_OS_STRINGS.get_static_with(|| Cow::Borrowed(OsStr::new("static-os-string")));
+
+    let first_symbol = GLOBAL_OS_STRINGS.get(OsString::from("ignored-global-os-string"));
+    assert_eq!(first_symbol, OsStr::new("ignored-global-os-string"));
+    let from_global = GLOBAL_OS_STRINGS.get(OsStr::new("shared_is_separate_os_string"));
+    let shared = OsStringPool::default();
+    let from_shared = shared.get(OsString::from("shared_is_separate_os_string"));
+    assert!(!Pooled::ptr_eq(&from_shared, &from_global));
+    let from_shared_borrowed = shared.get(OsStr::new("shared_is_separate_os_string"));
+    assert!(Pooled::ptr_eq(&from_shared, &from_shared_borrowed));
+
+    // Test both directions of partialeq
+    assert_eq!(from_shared, from_global);
+    assert_eq!(from_global, from_shared);
+
+    assert_ne!(first_symbol, from_shared);
+    assert_ne!(from_shared, first_symbol);
+
+    assert!(from_shared < OsStr::new("zzz"));
+    assert!(from_shared > OsStr::new("aaa"));
+
+    let from_static = GLOBAL_OS_STRINGS.get(OsStr::new("static-os-string"));
+    assert_eq!(STATIC_OS_STRING, from_static);
+    assert!(Pooled::ptr_eq(&*STATIC_OS_STRING, &from_static));
+}
+
+#[test]
+fn c_strings() {
+    static GLOBAL_C_STRINGS: CStringPool = CStringPool::new();
+    static STATIC_C_STRING: StaticPooledCString =
+        GLOBAL_C_STRINGS.get_static_with(|| Cow::Owned(CString::new("static").unwrap()));
+
+    let from_global = GLOBAL_C_STRINGS.get(CString::new("c-string").unwrap());
+    let shared = SharedPool::<CString>::default();
+    let from_shared = shared.get(CString::new("c-string").unwrap().as_c_str());
+    assert!(!Pooled::ptr_eq(&from_shared, &from_global));
+    assert_eq!(from_shared, from_global);
+    assert_eq!(from_global, CString::new("c-string").unwrap().as_c_str());
+
+    // The pointer handed out must be stable across lookups.
+    let again = GLOBAL_C_STRINGS.get(CString::new("c-string").unwrap());
+    assert_eq!(from_global.as_ptr(), again.as_ptr());
+
+    let string = GLOBAL_STRINGS.get("c-string");
+    let converted = GLOBAL_C_STRINGS.get_from_str(&string).unwrap();
+    assert!(Pooled::ptr_eq(&converted, &from_global));
+    assert!(GLOBAL_C_STRINGS.get_from_str("interior\0nul").is_err());
+
+    let from_static = GLOBAL_C_STRINGS.get_from_str("static").unwrap();
+    assert_eq!(STATIC_C_STRING, from_static);
+}
+
+#[test]
+fn symbols() {
+    assert_eq!(std::mem::size_of::<Symbol>(), 4);
+
+    let pool = StringPool::default();
+    let permanent = pool.symbol("permanent");
+    assert_eq!(pool.resolve(permanent).unwrap(), "permanent");
+    // Requesting the symbol again returns the same symbol.
+    assert_eq!(pool.symbol(String::from("permanent")), permanent);
+    // Permanent values survive all of their Pooled copies being dropped.
+    drop(pool.get("permanent"));
+    let pooled: Vec<SharedString> = pool.pooled();
+    assert_eq!(pooled.len(), 1);
+    assert_eq!(pooled[0].symbol(), permanent);
+
+    // Symbols from Pooled are only valid while the Pooled is retained.
+    let retained = pool.get("retained");
+    let symbol = retained.symbol();
+    assert_ne!(symbol, permanent);
+    assert!(Pooled::ptr_eq(&pool.resolve(symbol).unwrap(), &retained));
+    drop(retained);
+    assert!(pool.resolve(symbol).is_none());
+
+    let global = GLOBAL_STRINGS.symbol("global-symbol");
+    assert_eq!(GLOBAL_STRINGS.resolve(global).unwrap(), "global-symbol");
+}
+
+#[test]
+fn weak() {
+    let pool = StringPool::default();
+    let strong = pool.get("weak");
+    let weak = Pooled::downgrade(&strong);
+    let upgraded = weak.upgrade().unwrap();
+    assert!(Pooled::ptr_eq(&strong, &upgraded));
+    drop(upgraded);
+
+    // Weak references do not keep the value alive.
+    drop(strong);
+    assert!(weak.upgrade().is_none());
+    let pooled: Vec<SharedString> = pool.pooled();
+    assert!(pooled.is_empty());
+
+    // A new value in the same slot is not returned by the old weak reference.
+    let replacement = pool.get("weak");
+    assert!(weak.upgrade().is_none());
+    drop(replacement);
+}
+
+#[test]
+fn multithreaded_weak_upgrade() {
+    static WEAK_STRINGS: GlobalPool<String> = GlobalPool::new();
+
+    let mut threads = Vec::new();
+    for _ in 0..4 {
+        threads.push(thread::spawn(|| {
+            for _ in 0..1000 {
+                let strong = WEAK_STRINGS.get("multithreaded-weak");
+                let weak = Pooled::downgrade(&strong);
+                drop(strong);
+                if let Some(upgraded) = weak.upgrade() {
+                    assert_eq!(upgraded, "multithreaded-weak");
+                }
+            }
+        }));
+    }
+    for t in threads {
+        t.join().unwrap();
+    }
+    (&WEAK_STRINGS).with_active_symbols(|symbols| {
+        assert!(!symbols.contains("multithreaded-weak"));
+        assert!(symbols.slots.iter().all(Option::is_none));
+    });
+}
+
+#[test]
+fn permanent() {
+    static LEAKED: GlobalPool<String> = GlobalPool::new();
+
+    let pool = StringPool::default();
+    let temporary = pool.get("temporary");
+    assert!(!Pooled::is_permanent(&temporary));
+    let permanent = pool.make_permanent("permanent");
+    assert!(Pooled::is_permanent(&permanent));
+    drop(permanent);
+    drop(temporary);
+
+    let pooled: Vec<SharedString> = pool.pooled();
+    assert_eq!(pooled.len(), 1);
+    assert_eq!(pooled[0], "permanent");
+    assert!(Pooled::is_permanent(&pooled[0]));
+    drop(pooled);
+
+    let leaked: &'static str = LEAKED.get("leaked").leak();
+    assert_eq!(leaked, "leaked");
+    let again = LEAKED.get("leaked");
+    assert!(Pooled::is_permanent(&again));
+    assert!(std::ptr::eq(leaked, &**again));
+    drop(again);
+    let pooled: Vec<GlobalString> = LEAKED.pooled();
+    assert_eq!(pooled.len(), 1);
+}
+
+#[test]
+fn lookup_only() {
+    let pool = StringPool::default();
+    assert!(!pool.contains("lookup"));
+    assert!(pool.get_existing("lookup").is_none());
+    let pooled: Vec<SharedString> = pool.pooled();
+    assert!(pooled.is_empty());
+
+    let inserted = pool.get("lookup");
+    assert!(pool.contains("lookup"));
+    assert!(Pooled::ptr_eq(
+        &pool.get_existing("lookup").unwrap(),
+        &inserted
+    ));
+    drop(inserted);
+    assert!(!pool.contains("lookup"));
+
+    let paths = SharedPool::<PathBuf>::default();
+    let path = paths.get(Path::new("a/b"));
+    assert!(paths.contains(Path::new("a/b")));
+    assert!(!paths.contains(Path::new("a")));
+    assert!(Pooled::ptr_eq(
+        &paths.get_existing(&PathBuf::from("a/b")).unwrap(),
+        &path
+    ));
+
+    let buffer = GLOBAL_BUFFERS.get(&b"lookup-only"[..]);
+    assert!(GLOBAL_BUFFERS.contains(b"lookup-only"));
+    assert!(GLOBAL_BUFFERS
+        .get_existing(b"lookup-only-missing")
+        .is_none());
+    drop(buffer);
+}
+
+#[test]
+fn entries() {
+    let pool = StringPool::default();
+    let Entry::Vacant(vacant) = pool.entry("entry") else {
+        unreachable!("pool is empty")
+    };
+    assert_eq!(vacant.value(), "entry");
+    // Vacant entries don't insert the value until requested.
+    assert!(!pool.contains("entry"));
+    let inserted = vacant.insert();
+    assert!(pool.contains("entry"));
+
+    let Entry::Occupied(existing) = pool.entry(String::from("entry")) else {
+        unreachable!("value was inserted")
+    };
+    assert!(Pooled::ptr_eq(&existing, &inserted));
+    assert!(Pooled::ptr_eq(&pool.entry("entry").or_insert(), &inserted));
+
+    let other = GLOBAL_STRINGS.entry("entries-global").or_insert();
+    assert_eq!(other, "entries-global");
+}
+
+#[test]
+fn capacity() {
+    let pool = StringPool::default();
+    assert!(pool.is_empty());
+    assert_eq!(pool.len(), 0);
+    pool.reserve(16);
+    assert!(pool.capacity() >= 16);
+
+    let values = (0..16)
+        .map(|index| pool.get(index.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(pool.len(), 16);
+    assert!(!pool.is_empty());
+
+    // Release the second half of the values, leaving free slots at the end of
+    // the pool's storage.
+    let (first_half, second_half) = values.split_at(8);
+    let first_half = first_half.to_vec();
+    drop(second_half.to_vec());
+    drop(values);
+    assert_eq!(pool.len(), 8);
+
+    pool.shrink_to_fit();
+    assert!(pool.capacity() >= 8);
+    pool.with_active_symbols(|symbols| {
+        assert_eq!(symbols.slots.len(), 8);
+        assert!(symbols.free_slots.is_empty());
+    });
+
+    // New values can still be inserted after shrinking.
+    let new_value = pool.get("new");
+    assert_eq!(new_value.symbol().index(), 8);
+    drop(first_half);
+    assert_eq!(pool.len(), 1);
+
+    let buffer = GLOBAL_BUFFERS.get(&b"capacity-test"[..]);
+    assert!(!GLOBAL_BUFFERS.is_empty());
+    assert!(GLOBAL_BUFFERS.capacity() >= 1);
+    drop(buffer);
+}
+
+#[test]
+fn visiting() {
+    let pool = StringPool::default();
+    let values = ["a", "bb", "ccc"]
+        .into_iter()
+        .map(|value| pool.get(value))
+        .collect::<Vec<_>>();
+
+    let mut visited = Vec::new();
+    pool.for_each(|value| visited.push(value.to_string()));
+    visited.sort();
+    assert_eq!(visited, ["a", "bb", "ccc"]);
+
+    let total_len = pool.fold(0, |total, value| total + value.len());
+    assert_eq!(total_len, 6);
+
+    let snapshot = pool.iter_snapshot();
+    assert_eq!(snapshot.len(), 3);
+    let mut borrowed = snapshot.iter().map(|value| &**value).collect::<Vec<_>>();
+    borrowed.sort_unstable();
+    assert_eq!(borrowed, ["a", "bb", "ccc"]);
+
+    // Visiting the pool must not create any new references.
+    for value in &values {
+        assert_eq!(Arc::strong_count(&value.0 .0), 3);
+    }
+
+    // Values dropped while the snapshot holds the lock are removed once it is
+    // dropped.
+    drop(values);
+    assert_eq!(snapshot.len(), 3);
+    drop(snapshot);
+    assert_eq!(pool.len(), 0);
+}
+
+#[test]
+fn sharded() {
+    static SHARDED: ShardedGlobalPool<String, RandomState, 4> = ShardedGlobalPool::new();
+
+    let values = (0..64)
+        .map(|index| SHARDED.get(format!("sharded-{index}")))
+        .collect::<Vec<_>>();
+    assert_eq!(SHARDED.len(), 64);
+    for (index, value) in values.iter().enumerate() {
+        let again = SHARDED.get(format!("sharded-{index}"));
+        assert!(Pooled::ptr_eq(value, &again));
+        assert!(SHARDED.contains(&format!("sharded-{index}")));
+    }
+
+    // Values in different shards can share an index within their shard, but
+    // must never be considered the same value or share a symbol.
+    for (index, a) in values.iter().enumerate() {
+        for b in &values[index + 1..] {
+            assert!(!Pooled::ptr_eq(a, b));
+            assert_ne!(a, b);
+            assert_ne!(a.symbol(), b.symbol());
+        }
+    }
+
+    let pooled: Vec<ShardedGlobalString<RandomState, 4>> = SHARDED.pooled();
+    assert_eq!(pooled.len(), 64);
+    drop(pooled);
+    drop(values);
+    assert!(SHARDED.is_empty());
+    assert!(SHARDED.get_existing("sharded-0").is_none());
+
+    let shared = ShardedSharedPool::<String>::default();
+    let a = shared.get("a");
+    assert!(Pooled::ptr_eq(&a, &shared.get(String::from("a"))));
+    let other = ShardedSharedPool::<String>::default();
+    let other_a = other.get("a");
+    assert!(!Pooled::ptr_eq(&a, &other_a));
+    assert_eq!(a, other_a);
+    assert_eq!(shared.len(), 1);
+    drop(a);
+    assert!(shared.is_empty());
+}
+
+#[test]
+fn sharded_features() {
+    static NAMED: ShardedGlobalPool<String, RandomState, 4> = ShardedGlobalPool::named("sharded");
+    static EVENTS: AtomicUsize = AtomicUsize::new(0);
+
+    assert_eq!(NAMED.name(), Some("sharded"));
+    assert_eq!(
+        format!("{NAMED:?}"),
+        r#"ShardedGlobalPool { name: Some("sharded"), shards: 4, .. }"#
+    );
+    NAMED.add_observer(|_| {
+        EVENTS.fetch_add(1, Ordering::Relaxed);
+    });
+    drop(NAMED.get("observed"));
+    assert_eq!(EVENTS.load(Ordering::Relaxed), 2);
+    NAMED.clear_observers();
+
+    NAMED.set_release_mode(ReleaseMode::Deferred { threshold: 100 });
+    assert_eq!(
+        NAMED.release_mode(),
+        ReleaseMode::Deferred { threshold: 100 }
+    );
+    drop(NAMED.get("queued"));
+    assert!(NAMED.contains("queued"));
+    assert_eq!(NAMED.collect(), 1);
+    assert!(NAMED.is_empty());
+    NAMED.set_release_mode(ReleaseMode::Immediate);
+
+    let pool = ShardedSharedPool::<String>::named("sharded-shared");
+    assert_eq!(pool.name(), Some("sharded-shared"));
+    let events = Arc::new(AtomicUsize::new(0));
+    let observed = events.clone();
+    pool.add_observer(move |_| {
+        observed.fetch_add(1, Ordering::Relaxed);
+    });
+    drop(pool.get("observed"));
+    assert_eq!(events.load(Ordering::Relaxed), 2);
+
+    // Queued releases are collected once the final handle is dropped, rather
+    // than keeping the pool alive through the handles held by its values.
+    pool.set_release_mode(ReleaseMode::Deferred { threshold: 100 });
+    let value = pool.get("queued");
+    let weak = Pooled::downgrade(&value);
+    let clone = pool.clone();
+    drop(value);
+    drop(pool);
+    assert_eq!(clone.len(), 1);
+    drop(clone);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn sharded_lookups() {
+    static SHARDED: ShardedGlobalPool<String, RandomState, 4> =
+        ShardedGlobalPool::new().with_limits(PoolLimits::new().with_max_entries(8));
+    static OS_STRINGS: ShardedGlobalPool<OsString> = ShardedGlobalPool::new();
+
+    // Symbols record the value's shard, so each resolves to its own value.
+    let symbols = (0..8)
+        .map(|index| SHARDED.symbol(format!("symbol-{index}")))
+        .collect::<Vec<_>>();
+    for (index, symbol) in symbols.iter().enumerate() {
+        let resolved = SHARDED.resolve(*symbol).unwrap();
+        assert_eq!(resolved, &*format!("symbol-{index}"));
+        assert!(Pooled::is_permanent(&resolved));
+    }
+
+    // The limit applies across all shards, regardless of which shard the
+    // next value belongs to.
+    assert_eq!(SHARDED.limits().max_entries, Some(8));
+    assert_eq!(SHARDED.len(), 8);
+    for index in 0..16 {
+        assert_eq!(
+            SHARDED.get_checked(format!("over-{index}")),
+            Err(Error::TooManyEntries)
+        );
+    }
+    assert_eq!(SHARDED.try_get("over"), Err(Error::TooManyEntries));
+    assert!(panic::catch_unwind(|| SHARDED.get("over")).is_err());
+    assert_eq!(SHARDED.try_get("symbol-0").unwrap(), "symbol-0");
+    SHARDED.set_limits(PoolLimits::new());
+    assert!(SHARDED.get_checked("allowed").is_ok());
+
+    let shared = ShardedSharedPool::<String>::default()
+        .with_limits(PoolLimits::new().with_max_entries(1).with_max_value_len(8));
+    let entry = match shared.entry("entry") {
+        Entry::Occupied(_) => unreachable!("the pool is empty"),
+        Entry::Vacant(vacant) => vacant.insert(),
+    };
+    assert!(matches!(shared.entry("entry"), Entry::Occupied(_)));
+    assert_eq!(shared.try_get("other"), Err(Error::TooManyEntries));
+    drop(entry);
+    assert_eq!(
+        shared.get_checked("too long a value"),
+        Err(Error::ValueTooLong)
+    );
+    let symbol = shared.try_symbol("symbol").unwrap();
+    assert_eq!(shared.resolve(symbol).unwrap(), "symbol");
+    assert!(Pooled::is_permanent(&shared.make_permanent("symbol")));
+
+    let os_string: ShardedGlobalOsString = OS_STRINGS.get(OsStr::new("os"));
+    assert_eq!(os_string, OsStr::new("os"));
+    let c_strings = ShardedSharedPool::<CString>::default();
+    let c_string: ShardedSharedCString = c_strings.get(CString::new("c").unwrap());
+    assert_eq!(c_string.to_str(), Ok("c"));
+}
+
+#[test]
+fn multithreaded_sharded() {
+    static SHARDED: ShardedGlobalPool<String> = ShardedGlobalPool::new();
+
+    let mut threads = Vec::new();
+    for thread_index in 0..4 {
+        threads.push(thread::spawn(move || {
+            for index in 0..1000 {
+                let value = SHARDED.get(format!("{}", (index + thread_index) % 50));
+                assert_eq!(value, format!("{}", (index + thread_index) % 50).as_str());
+            }
+        }));
+    }
+    for t in threads {
+        t.join().unwrap();
+    }
+    assert!(SHARDED.is_empty());
+}
+
+#[test]
+fn thread_cache() {
+    static CACHED: GlobalPool<String> = GlobalPool::new();
+
+    let first = CACHED.get_cached("cached");
+    let second = CACHED.get_cached(String::from("cached"));
+    assert!(Pooled::ptr_eq(&first, &second));
+    assert!(Pooled::ptr_eq(&first, &CACHED.get("cached")));
+
+    // The thread cache keeps the value alive after our copies are dropped.
+    drop((first, second));
+    assert!(CACHED.contains("cached"));
+    CACHED.clear_thread_cache();
+    assert!(!CACHED.contains("cached"));
+
+    // Values cached by a thread are released when it exits.
+    thread::spawn(|| {
+        for _ in 0..10 {
+            let value = CACHED.get_cached("cached-in-thread");
+            assert_eq!(value, "cached-in-thread");
+        }
+        assert!(CACHED.contains("cached-in-thread"));
+    })
+    .join()
+    .unwrap();
+    assert!(!CACHED.contains("cached-in-thread"));
+
+    // Filling the cache beyond its capacity evicts and releases values.
+    for index in 0..1000 {
+        drop(CACHED.get_cached(index.to_string()));
+    }
+    assert!(CACHED.len() <= crate::cache::THREAD_CACHE_ENTRIES);
+    CACHED.clear_thread_cache();
+    assert!(CACHED.is_empty());
+}
+
+#[test]
+fn frozen() {
+    static FROZEN: GlobalPool<String> = GlobalPool::new();
+
+    let pool = StringPool::default();
+    let hello = pool.get("hello");
+    let frozen = pool.freeze();
+    assert_eq!(frozen.len(), 1);
+    assert!(SharedString::ptr_eq(&frozen.get("hello").unwrap(), &hello));
+    assert!(frozen.contains("hello"));
+    assert!(frozen.get("world").is_none());
+
+    // Values inserted after freezing are only found by falling back to the
+    // original pool.
+    let world = frozen.get_or_insert("world");
+    assert!(SharedString::ptr_eq(&world, &pool.get("world")));
+    assert!(!frozen.contains("world"));
+
+    // The frozen pool can be read from multiple threads.
+    let frozen = Arc::new(frozen);
+    let threads = (0..4)
+        .map(|_| {
+            let frozen = frozen.clone();
+            thread::spawn(move || frozen.get("hello").unwrap())
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        assert!(SharedString::ptr_eq(&thread.join().unwrap(), &hello));
+    }
+
+    // The frozen pool keeps its values allocated until it is dropped.
+    drop((hello, world));
+    assert_eq!(pool.len(), 1);
+    drop(frozen);
+    assert!(pool.is_empty());
+
+    let global = FROZEN.get("global");
+    let frozen = FROZEN.freeze();
+    assert!(Pooled::ptr_eq(&frozen.get("global").unwrap(), &global));
+    drop(global);
+    assert!(FROZEN.contains("global"));
+    drop(frozen);
+    assert!(!FROZEN.contains("global"));
+}
+
+#[test]
+fn deferred_release() {
+    static DEFERRED: GlobalPool<String> = GlobalPool::new();
+
+    let pool = StringPool::default();
+    assert_eq!(pool.release_mode(), ReleaseMode::Immediate);
+    pool.set_release_mode(ReleaseMode::Deferred { threshold: 100 });
+    assert_eq!(
+        pool.release_mode(),
+        ReleaseMode::Deferred { threshold: 100 }
+    );
+
+    // Dropping the final reference queues the value rather than removing it.
+    drop(pool.get("queued"));
+    assert!(pool.contains("queued"));
+    assert_eq!(pool.collect(), 1);
+    assert!(pool.is_empty());
+
+    // Values that are referenced again before being collected are kept.
+    let revived = pool.get("revived");
+    let weak = Pooled::downgrade(&revived);
+    drop(revived);
+    let revived = weak.upgrade().unwrap();
+    assert_eq!(pool.collect(), 0);
+    assert!(pool.contains("revived"));
+    drop(revived);
+    assert_eq!(pool.collect(), 1);
+
+    // Retrieving a value collects the queue.
+    drop(pool.get("first"));
+    let second = pool.get("second");
+    assert_eq!(pool.len(), 1);
+    drop(second);
+
+    // Reaching the threshold collects the queue.
+    pool.set_release_mode(ReleaseMode::Deferred { threshold: 4 });
+    let values = (0..10).map(|i| pool.get(i.to_string())).collect::<Vec<_>>();
+    drop(values);
+    assert!(pool.len() < 4);
+
+    // Switching back to immediate releases leaves queued values until the
+    // next collection.
+    pool.set_release_mode(ReleaseMode::Immediate);
+    pool.collect();
+    drop(pool.get("immediate"));
+    assert!(pool.is_empty());
+
+    DEFERRED.set_release_mode(ReleaseMode::Deferred {
+        threshold: usize::MAX,
+    });
+    let values = (0..100)
+        .map(|i| DEFERRED.get(i.to_string()))
+        .collect::<Vec<GlobalString>>();
+    drop(values);
+    assert_eq!(DEFERRED.len(), 100);
+    assert_eq!(DEFERRED.collect(), 100);
+    assert!(DEFERRED.is_empty());
+}
+
+#[test]
+fn multithreaded_deferred_release() {
+    static DEFERRED: GlobalPool<String> = GlobalPool::new();
+    DEFERRED.set_release_mode(ReleaseMode::Deferred { threshold: 16 });
+
+    let threads = (0..4)
+        .map(|_| {
+            thread::spawn(|| {
+                for i in 0..1000 {
+                    let strong = DEFERRED.get((i % 10).to_string());
+                    let weak = Pooled::downgrade(&strong);
+                    drop(strong);
+                    if let Some(upgraded) = weak.upgrade() {
+                        assert_eq!(upgraded, (i % 10).to_string().as_str());
+                    }
+                    if i % 100 == 0 {
+                        DEFERRED.collect();
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    DEFERRED.collect();
+    (&DEFERRED).with_active_symbols(|symbols| {
+        assert!(symbols.active.is_empty());
+        assert!(symbols.slots.iter().all(Option::is_none));
+    });
+}
+
+/// A collection that drops every value it is created from.
+struct Discard;
+
+impl<T> FromIterator<T> for Discard {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        iter.into_iter().for_each(drop);
+        Discard
+    }
+}
+
+#[test]
+fn reentrant_drops() {
+    static REENTRANT: GlobalPool<String> = GlobalPool::new();
+    static SHARDED_REENTRANT: ShardedGlobalPool<String> = ShardedGlobalPool::new();
+
+    let pool = StringPool::default();
+
+    // The handles created by `pooled()` are the final references to these
+    // values, and they are dropped while the pool is locked.
+    drop(pool.get("a"));
+    pool.make_permanent("permanent");
+    let Discard = pool.pooled();
+    assert_eq!(pool.len(), 1);
+
+    // Dropping the final reference from within a visitor.
+    let mut held = Some(pool.get("held"));
+    pool.for_each(|_| drop(held.take()));
+    assert!(!pool.contains("held"));
+    let held = pool.get("held");
+    pool.fold(Some(held), |_, _| None);
+    assert!(!pool.contains("held"));
+
+    // Values dropped while locked are deferred the same way when releases are
+    // already being deferred.
+    pool.set_release_mode(ReleaseMode::Deferred { threshold: 1 });
+    let mut held = Some(pool.get("held"));
+    pool.for_each(|_| drop(held.take()));
+    assert!(pool.contains("held"));
+    assert_eq!(pool.collect(), 1);
+
+    let mut held = Some(REENTRANT.get("held"));
+    REENTRANT.for_each(|_| drop(held.take()));
+    let Discard = REENTRANT.pooled();
+    assert!(REENTRANT.is_empty());
+
+    drop(SHARDED_REENTRANT.get("held"));
+    let held = SHARDED_REENTRANT.get("held");
+    let Discard = SHARDED_REENTRANT.pooled();
+    assert!(SHARDED_REENTRANT.contains("held"));
+    drop(held);
+    assert!(SHARDED_REENTRANT.is_empty());
+
+    let sharded = ShardedSharedPool::<String>::default();
+    let values = (0..100)
+        .map(|i| sharded.get(i.to_string()))
+        .collect::<Vec<_>>();
+    let Discard = sharded.pooled();
+    drop(values);
+    assert!(sharded.is_empty());
+}
+
+/// A value that panics when it is hashed or boxed, depending on its contents.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+struct Fragile(&'static str);
+
+impl Hash for Fragile {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        assert_ne!(self.0, "panic-hash");
+        self.0.hash(state);
+    }
+}
+
+impl Poolable for Fragile {
+    type Borrowed = Self;
+    type Boxed = Self;
+
+    fn boxed(self) -> Self::Boxed {
+        assert_ne!(self.0, "panic-boxed");
+        self
+    }
+}
+
+#[test]
+fn panic_safety() {
+    static FRAGILE: GlobalPool<Fragile> = GlobalPool::new();
+    static LAZY_PANICKED: AtomicBool = AtomicBool::new(false);
+    static LAZY: GlobalPool<String> = GlobalPool::with_hasher_init(|| {
+        assert!(LAZY_PANICKED.swap(true, Ordering::Relaxed));
+        RandomState::new()
+    });
+
+    let kept = FRAGILE.get(Cow::Owned(Fragile("kept")));
+    // Leave a free slot behind to be reused.
+    drop(FRAGILE.get(Cow::Owned(Fragile("freed"))));
+
+    assert!(panic::catch_unwind(|| FRAGILE.get(Cow::Owned(Fragile("panic-boxed")))).is_err());
+    assert!(panic::catch_unwind(|| FRAGILE.get(Cow::Owned(Fragile("panic-hash")))).is_err());
+    // Panicking while the pool is locked poisons its mutex.
+    assert!(panic::catch_unwind(|| FRAGILE.for_each(|_| panic!())).is_err());
+
+    let after = FRAGILE.get(Cow::Owned(Fragile("after")));
+    assert!(Pooled::ptr_eq(
+        &kept,
+        &FRAGILE.get(Cow::Owned(Fragile("kept")))
+    ));
+    (&FRAGILE).with_active_symbols(|symbols| {
+        assert_eq!(symbols.active.len(), 2);
+        assert_eq!(
+            symbols.slots.len(),
+            symbols.active.len() + symbols.free_slots.len()
+        );
+        for data in &symbols.active {
+            let slot = symbols.slots[data.0.index].as_ref().unwrap();
+            assert!(Arc::ptr_eq(&slot.0 .0, &data.0));
+        }
+        for free_slot in &symbols.free_slots {
+            assert!(symbols.slots[*free_slot].is_none());
+        }
+    });
+    drop((kept, after));
+    assert!(FRAGILE.is_empty());
+
+    // A panic while lazily initializing the hasher is retried on next use.
+    assert!(panic::catch_unwind(|| LAZY.get("lazy")).is_err());
+    assert_eq!(LAZY.get("lazy"), "lazy");
+}
+
+#[test]
+fn try_get() {
+    static TRY_STRINGS: GlobalPool<String> = GlobalPool::new();
+
+    let pool = StringPool::default();
+    let hello = pool.try_get("hello").unwrap();
+    assert!(SharedString::ptr_eq(
+        &hello,
+        &pool.try_get_existing("hello").unwrap().unwrap()
+    ));
+    assert_eq!(pool.try_get_existing("world"), Ok(None));
+
+    // The pool is locked while the visitor is invoked.
+    pool.for_each(|_| {
+        assert_eq!(pool.try_get("world"), Err(Error::WouldBlock));
+        assert_eq!(pool.try_get_existing("hello"), Err(Error::WouldBlock));
+    });
+
+    // Another thread holding the lock.
+    let barrier = Arc::new(Barrier::new(2));
+    let locked = thread::spawn({
+        let pool = pool.clone();
+        let barrier = barrier.clone();
+        move || {
+            pool.for_each(|_| {
+                barrier.wait();
+                barrier.wait();
+            });
+        }
+    });
+    barrier.wait();
+    assert_eq!(pool.try_get("world"), Err(Error::WouldBlock));
+    barrier.wait();
+    locked.join().unwrap();
+    assert_eq!(pool.try_get("world").unwrap(), "world");
+
+    let global = TRY_STRINGS.try_get("hello").unwrap();
+    assert!(Pooled::ptr_eq(
+        &global,
+        &TRY_STRINGS.try_get_existing("hello").unwrap().unwrap()
+    ));
+    TRY_STRINGS.for_each(|_| {
+        assert_eq!(TRY_STRINGS.try_get("hello"), Err(Error::WouldBlock));
+        assert_eq!(
+            TRY_STRINGS.try_get_existing("hello"),
+            Err(Error::WouldBlock)
+        );
+    });
+}
+
+#[test]
+fn get_checked() {
+    static CHECKED: GlobalPool<String> =
+        GlobalPool::new().with_limits(PoolLimits::new().with_max_value_len(5));
+    static STATIC_HELLO: StaticPooledString = CHECKED.get_static("hello");
+    static STATIC_GOODBYE: StaticPooledString = CHECKED.get_static("goodbye");
+
+    let pool = StringPool::default().with_limits(PoolLimits::new().with_max_entries(1));
+    let hello = pool.get_checked("hello").unwrap();
+    assert!(Pooled::ptr_eq(&pool.get_checked("hello").unwrap(), &hello));
+    assert_eq!(pool.get_checked("world"), Err(Error::TooManyEntries));
+
+    // Unlike try_get(), another thread holding the lock is waited for.
+    let barrier = Arc::new(Barrier::new(2));
+    let locked = thread::spawn({
+        let pool = pool.clone();
+        let barrier = barrier.clone();
+        move || {
+            pool.for_each(|_| {
+                barrier.wait();
+                thread::sleep(std::time::Duration::from_millis(10));
+            });
+        }
+    });
+    barrier.wait();
+    assert!(Pooled::ptr_eq(&pool.get_checked("hello").unwrap(), &hello));
+    locked.join().unwrap();
+
+    assert_eq!(CHECKED.get_checked("hello").unwrap(), "hello");
+    assert_eq!(CHECKED.get_checked("goodbye"), Err(Error::ValueTooLong));
+
+    // Static values wait for the lock as well, and report errors on each
+    // access until they are initialized.
+    let held = CHECKED.get("held");
+    let barrier = Arc::new(Barrier::new(2));
+    let locked = thread::spawn({
+        let barrier = barrier.clone();
+        move || {
+            CHECKED.for_each(|_| {
+                barrier.wait();
+                thread::sleep(std::time::Duration::from_millis(10));
+            });
+        }
+    });
+    barrier.wait();
+    assert_eq!(STATIC_HELLO.get_checked().unwrap(), "hello");
+    locked.join().unwrap();
+    assert!(Pooled::ptr_eq(
+        STATIC_HELLO.get_checked().unwrap(),
+        STATIC_HELLO.get()
+    ));
+    assert_eq!(STATIC_GOODBYE.get_checked(), Err(Error::ValueTooLong));
+    assert_eq!(STATIC_GOODBYE.get_checked(), Err(Error::ValueTooLong));
+    drop(held);
+}
+
+#[test]
+fn fallible() {
+    static C_STRINGS: CStringPool = CStringPool::new();
+    static STATIC_C_STRING: StaticPooledCString =
+        C_STRINGS.get_static_with(|| Cow::Owned(CString::new("static").unwrap()));
+
+    let pool = StringPool::default();
+    let hello = pool.get("hello");
+    assert_eq!(hello.try_symbol(), Ok(hello.symbol()));
+
+    let c_string = C_STRINGS.try_get_from_str("hello").unwrap();
+    assert_eq!(c_string.to_str(), Ok("hello"));
+    let err = C_STRINGS.try_get_from_str("nul\0byte").unwrap_err();
+    assert!(matches!(err, Error::InteriorNul(_)));
+    assert!(std::error::Error::source(&err).is_some());
+
+    // Static values aren't initialized while the pool is locked, and they are
+    // initialized on a later access instead.
+    C_STRINGS.for_each(|_| {
+        assert_eq!(STATIC_C_STRING.try_get(), Err(Error::WouldBlock));
+    });
+    assert_eq!(STATIC_C_STRING.try_get().unwrap().to_str(), Ok("static"));
+    assert!(Pooled::ptr_eq(
+        STATIC_C_STRING.try_get().unwrap(),
+        STATIC_C_STRING.get()
+    ));
+
+    assert_eq!(Error::WouldBlock.to_string(), "the pool is locked");
+}
+
+#[test]
+fn limits() {
+    static LIMITED: GlobalPool<String> =
+        GlobalPool::new().with_limits(PoolLimits::new().with_max_entries(1));
+
+    let pool = StringPool::default().with_limits(
+        PoolLimits::new()
+            .with_max_entries(2)
+            .with_max_bytes(8)
+            .with_max_value_len(5),
+    );
+    assert_eq!(pool.try_get("toolong"), Err(Error::ValueTooLong));
+    let hello = pool.try_get("hello").unwrap();
+    assert_eq!(pool.try_get("world"), Err(Error::TooManyBytes));
+    let hi = pool.try_get("hi").unwrap();
+    assert_eq!(pool.try_get("a"), Err(Error::TooManyEntries));
+    // Existing values can still be retrieved.
+    assert!(Pooled::ptr_eq(&pool.try_get("hello").unwrap(), &hello));
+    assert!(Pooled::ptr_eq(&pool.get("hi"), &hi));
+
+    // Freeing a value makes room for new values.
+    drop(hi);
+    let a = pool.try_get("a").unwrap();
+    assert_eq!(pool.try_get("b"), Err(Error::TooManyEntries));
+
+    // Limits can be adjusted at runtime without affecting existing values.
+    pool.set_limits(PoolLimits::new());
+    assert_eq!(pool.limits(), PoolLimits::new());
+    let world = pool.try_get("world").unwrap();
+    pool.set_limits(PoolLimits::new().with_max_entries(1));
+    assert_eq!(pool.len(), 3);
+    assert_eq!(pool.try_get("b"), Err(Error::TooManyEntries));
+    assert!(panic::catch_unwind(|| pool.get("b")).is_err());
+    drop((hello, a, world));
+
+    assert_eq!(LIMITED.limits(), PoolLimits::new().with_max_entries(1));
+    let first = LIMITED.try_get("first").unwrap();
+    assert_eq!(LIMITED.try_get("second"), Err(Error::TooManyEntries));
+    drop(first);
+    assert!(LIMITED.try_get("second").is_ok());
+}
+
+#[test]
+fn limits_without_panicking() {
+    static LIMITED: GlobalPool<String> =
+        GlobalPool::new().with_limits(PoolLimits::new().with_max_entries(1));
+    static C_STRINGS: CStringPool =
+        CStringPool::new().with_limits(PoolLimits::new().with_max_value_len(6));
+
+    let pool = StringPool::default().with_limits(PoolLimits::new().with_max_entries(1));
+    let Entry::Vacant(entry) = pool.entry("hello") else {
+        unreachable!()
+    };
+    let hello = entry.try_insert().unwrap();
+    let Entry::Vacant(entry) = pool.entry("world") else {
+        unreachable!()
+    };
+    assert_eq!(entry.try_insert(), Err(Error::TooManyEntries));
+
+    assert!(Pooled::ptr_eq(
+        &pool.try_make_permanent("hello").unwrap(),
+        &hello
+    ));
+    assert!(Pooled::is_permanent(&hello));
+    assert_eq!(pool.try_make_permanent("world"), Err(Error::TooManyEntries));
+    assert_eq!(pool.try_symbol("hello"), Ok(hello.symbol()));
+    assert_eq!(pool.try_symbol("world"), Err(Error::TooManyEntries));
+    assert!(panic::catch_unwind(|| pool.symbol("world")).is_err());
+
+    let frozen = pool.freeze();
+    assert!(Pooled::ptr_eq(
+        &frozen.try_get_or_insert("hello").unwrap(),
+        &hello
+    ));
+    assert_eq!(
+        frozen.try_get_or_insert("world"),
+        Err(Error::TooManyEntries)
+    );
+    assert!(panic::catch_unwind(|| frozen.get_or_insert("world")).is_err());
+
+    let first = LIMITED.try_make_permanent("first").unwrap();
+    assert_eq!(LIMITED.try_symbol("first"), Ok(first.symbol()));
+    assert_eq!(
+        LIMITED.try_make_permanent("second"),
+        Err(Error::TooManyEntries)
+    );
+
+    assert!(C_STRINGS.get_from_str_checked("short").is_ok());
+    assert_eq!(
+        C_STRINGS.get_from_str_checked("too long"),
+        Err(Error::ValueTooLong)
+    );
+    assert!(matches!(
+        C_STRINGS.get_from_str_checked("nul\0"),
+        Err(Error::InteriorNul(_))
+    ));
+}
+
+#[test]
+#[cfg(feature = "stats")]
+fn stats() {
+    use crate::PoolStats;
+
+    static SHARDED: ShardedGlobalPool<String> = ShardedGlobalPool::new();
+    static CACHED: GlobalPool<String> = GlobalPool::new();
+
+    let pool = StringPool::default();
+    assert_eq!(pool.stats(), PoolStats::default());
+    let hello = pool.get("hello");
+    let world = pool.get("world");
+    let hello_again = pool.get("hello");
+    drop(world);
+    let reused = pool.get("reused");
+    assert!(pool.get_existing("hello").is_some());
+    assert!(!pool.contains("missing"));
+    assert_eq!(
+        pool.stats(),
+        PoolStats {
+            lookups: 6,
+            hits: 2,
+            misses: 4,
+            cached_hits: 0,
+            inserts: 3,
+            frees: 1,
+            peak_entries: 2,
+            slot_reuses: 1,
+        }
+    );
+    drop((hello, hello_again, reused));
+    assert_eq!(pool.stats().frees, 3);
+
+    let pool = StringPool::default().with_limits(PoolLimits::new().with_max_entries(0));
+    assert!(pool.try_get("hello").is_err());
+    let stats = pool.stats();
+    assert_eq!((stats.misses, stats.inserts), (1, 0));
+
+    let values = (0..10)
+        .map(|i| SHARDED.get(i.to_string()))
+        .collect::<Vec<_>>();
+    let stats = SHARDED.stats();
+    assert_eq!((stats.inserts, stats.peak_entries), (10, 10));
+    drop(values);
+    assert_eq!(SHARDED.stats().frees, 10);
+    // The peak is tracked across shards rather than summing each shard's
+    // peak, which were reached at different times.
+    let values = (10..20)
+        .map(|i| SHARDED.get(i.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(SHARDED.stats().peak_entries, 10);
+    drop(values);
+
+    let first = CACHED.get_cached("hello");
+    let second = CACHED.get_cached("hello");
+    let stats = CACHED.stats();
+    assert_eq!(
+        (stats.lookups, stats.hits, stats.misses, stats.cached_hits),
+        (2, 1, 1, 1)
+    );
+    drop((first, second));
+    CACHED.clear_thread_cache();
+}
+
+#[test]
+fn memory_usage() {
+    let pool = StringPool::default();
+    assert_eq!(pool.memory_usage().total(), 0);
+
+    let hello = (0..100).map(|_| pool.get("hello")).collect::<Vec<_>>();
+    let world = pool.get("world");
+    let usage = pool.memory_usage();
+    assert_eq!(usage.values, 10);
+    assert_eq!(usage.unshared, 505);
+    assert!(usage.headers > 0);
+    assert!(usage.table > 0);
+    assert!(usage.slots >= 2 * std::mem::size_of::<usize>());
+    assert_eq!(
+        usage.total(),
+        usage.values + usage.headers + usage.table + usage.slots
+    );
+    assert_eq!(usage.saved(), usage.unshared - usage.total());
+
+    // A pool of values that are only referenced once costs more than it saves.
+    drop(hello);
+    let usage = pool.memory_usage();
+    assert_eq!(usage.unshared, 5);
+    assert_eq!(usage.saved(), 0);
+    drop(world);
+
+    let sharded = ShardedSharedPool::<String>::default();
+    let values = (0..10)
+        .map(|i| sharded.get(i.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(sharded.memory_usage().values, 10);
+    drop(values);
+    assert_eq!(sharded.memory_usage().values, 0);
+}
+
+#[test]
+fn observers() {
+    use std::sync::Mutex;
+
+    use crate::PoolEventKind;
+
+    static OBSERVED: GlobalPool<String> = GlobalPool::new();
+
+    let pool = StringPool::default();
+    let log = Arc::new(Mutex::new(Vec::new()));
+    pool.add_observer({
+        let log = log.clone();
+        move |event| {
+            log.lock()
+                .unwrap()
+                .push((event.kind, event.value.to_string(), event.index));
+            // Observers can use the pool they are observing.
+            if event.kind == PoolEventKind::Inserted && event.value == "outer" {
+                assert!(event.pool.contains("outer"));
+                drop(event.pool.get("inner"));
+            }
+        }
+    });
+
+    let outer = pool.get("outer");
+    assert_eq!(
+        std::mem::take(&mut *log.lock().unwrap()),
+        [
+            (PoolEventKind::Inserted, String::from("outer"), 0),
+            (PoolEventKind::Inserted, String::from("inner"), 1),
+            (PoolEventKind::Freed, String::from("inner"), 1),
+        ]
+    );
+
+    // Values dropped while the pool is locked are reported once it unlocks.
+    let mut outer = Some(outer);
+    pool.for_each(|_| drop(outer.take()));
+    assert_eq!(
+        std::mem::take(&mut *log.lock().unwrap()),
+        [(PoolEventKind::Freed, String::from("outer"), 0)]
+    );
+
+    // Deferred releases are reported when they are collected.
+    pool.set_release_mode(ReleaseMode::Deferred { threshold: 100 });
+    drop(pool.get("deferred"));
+    assert_eq!(log.lock().unwrap().len(), 1);
+    assert_eq!(pool.collect(), 1);
+    assert_eq!(
+        log.lock().unwrap().last(),
+        Some(&(PoolEventKind::Freed, String::from("deferred"), 0))
+    );
+
+    pool.clear_observers();
+    drop(pool.get("unobserved"));
+    assert_eq!(log.lock().unwrap().len(), 2);
+
+    let frees = Arc::new(AtomicUsize::new(0));
+    OBSERVED.add_observer({
+        let frees = frees.clone();
+        move |event| {
+            if event.kind == PoolEventKind::Freed {
+                frees.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    });
+    drop(OBSERVED.get("global"));
+    assert_eq!(frees.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn observers_ordered() {
+    use crate::PoolEventKind;
+
+    // An observer that mirrors the pool's contents must see each value's
+    // insertions and frees alternate, even when they happen on many threads.
+    let pool = StringPool::default();
+    let present = Arc::new(AtomicBool::new(false));
+    let misordered = Arc::new(AtomicBool::new(false));
+    pool.add_observer({
+        let present = present.clone();
+        let misordered = misordered.clone();
+        move |event| {
+            let inserted = event.kind == PoolEventKind::Inserted;
+            if present.swap(inserted, Ordering::Relaxed) == inserted {
+                misordered.store(true, Ordering::Relaxed);
+            }
+        }
+    });
+
+    let barrier = Barrier::new(4);
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                barrier.wait();
+                for _ in 0..10_000 {
+                    drop(pool.get("contended"));
+                }
+            });
+        }
+    });
+
+    assert!(!misordered.load(Ordering::Relaxed));
+    assert!(!present.load(Ordering::Relaxed));
+    assert_eq!(pool.len(), 0);
+}
+
+#[test]
+#[cfg(feature = "tracing")]
+fn tracing() {
+    use std::fmt::Debug;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Dispatch, Event, Metadata, Subscriber};
+
+    static TRACED: GlobalPool<String> = GlobalPool::new().with_name("traced");
+
+    #[derive(Default)]
+    struct Recorder {
+        spans: Mutex<Vec<String>>,
+        events: Mutex<Vec<(String, String)>>,
+    }
+
+    #[derive(Default)]
+    struct Fields {
+        message: String,
+        pool: String,
+    }
+
+    impl Visit for Fields {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "pool" {
+                self.pool = value.to_string();
+            }
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            if field.name() == "message" {
+                self.message = format!("{value:?}");
+            }
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut fields = Fields::default();
+            span.record(&mut fields);
+            let mut spans = self.spans.lock().unwrap();
+            spans.push(format!("{}:{}", span.metadata().name(), fields.pool));
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields::default();
+            event.record(&mut fields);
+            self.events
+                .lock()
+                .unwrap()
+                .push((fields.message, fields.pool));
+        }
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    fn contains(recorder: &Recorder, message: &str, pool: &str) -> bool {
+        recorder
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|event| event.0 == message && event.1 == pool)
+    }
+
+    let recorder = Arc::new(Recorder::default());
+    let dispatch = Dispatch::from(recorder.clone());
+    tracing::dispatcher::with_default(&dispatch, || {
+        drop(TRACED.get("hello"));
+
+        let pool = StringPool::named("shared");
+        assert_eq!(pool.name(), Some("shared"));
+        let held = pool.get("held");
+        // Every wait is reported, but the waiting thread may still acquire the
+        // lock without waiting if it is released first, so this is repeated
+        // until a wait is observed.
+        crate::set_lock_wait_threshold(Duration::ZERO);
+        let waited = || contains(&recorder, "waited for pool lock", "shared");
+        while !waited() {
+            let barrier = Barrier::new(2);
+            thread::scope(|scope| {
+                pool.for_each(|_| {
+                    scope.spawn(|| {
+                        barrier.wait();
+                        tracing::dispatcher::with_default(&dispatch, || drop(pool.get("waiter")));
+                    });
+                    barrier.wait();
+                    thread::sleep(Duration::from_millis(1));
+                });
+            });
+        }
+        crate::set_lock_wait_threshold(Duration::from_millis(1));
+        drop(held);
+    });
+
+    assert_eq!(TRACED.name(), Some("traced"));
+    assert_eq!(*recorder.spans.lock().unwrap(), ["initialize:traced"]);
+    assert!(contains(&recorder, "initialized pool", "traced"));
+    assert!(contains(&recorder, "inserted value", "traced"));
+    assert!(contains(&recorder, "pool capacity grew", "traced"));
+    assert!(contains(&recorder, "freed value", "traced"));
+    assert!(contains(&recorder, "inserted value", "shared"));
+    assert!(contains(&recorder, "freed value", "shared"));
+}
+
+#[test]
+fn named_debug() {
+    static NAMED: GlobalPool<String> = GlobalPool::named("named-debug-global");
+
+    assert_eq!(NAMED.name(), Some("named-debug-global"));
+    let value = NAMED.get("hello");
+    assert_eq!(
+        format!("{NAMED:?}"),
+        r#"GlobalPool { name: Some("named-debug-global"), len: 1, .. }"#
+    );
+    drop(value);
+
+    let pool = StringPool::named("named-debug-shared");
+    assert_eq!(pool.name(), Some("named-debug-shared"));
+    assert_eq!(
+        format!("{pool:?}"),
+        r#"SharedPool { name: Some("named-debug-shared"), len: 0, .. }"#
+    );
+}
+
+#[test]
+#[cfg(feature = "registry")]
+fn registry() {
+    use crate::registry::{self, Kind, PoolInfo};
+
+    static REGISTERED: GlobalPool<String> = GlobalPool::named("registry-global");
+    static UNNAMED: GlobalPool<PathBuf> = GlobalPool::new();
+    static SHARDED: ShardedGlobalPool<String> = ShardedGlobalPool::named("registry-sharded");
+
+    /// A hasher that is neither `Send` nor `Sync`.
+    #[derive(Default)]
+    struct LocalState(std::rc::Rc<RandomState>);
+
+    impl BuildHasher for LocalState {
+        type Hasher = <RandomState as BuildHasher>::Hasher;
+
+        fn build_hasher(&self) -> Self::Hasher {
+            self.0.build_hasher()
+        }
+    }
+
+    fn find(name: &str) -> Option<PoolInfo> {
+        registry::pools()
+            .into_iter()
+            .find(|info| info.name == Some(name))
+    }
+
+    // Pools are only listed once they are registered, and registering a pool
+    // again doesn't list it twice.
+    let global = REGISTERED.get("hello");
+    assert!(find("registry-global").is_none());
+    REGISTERED.register();
+    REGISTERED.register();
+    assert_eq!(
+        registry::pools()
+            .iter()
+            .filter(|info| info.name == Some("registry-global"))
+            .count(),
+        1
+    );
+    let info = find("registry-global").unwrap();
+    assert_eq!(info.kind, Kind::Global);
+    assert_eq!(info.value_type, std::any::type_name::<String>());
+    assert_eq!(info.len, 1);
+    assert_eq!(info.memory, REGISTERED.memory_usage());
+    drop(global);
+    assert_eq!(find("registry-global").unwrap().len, 0);
+
+    // Shared pools are listed until they are dropped, including while their
+    // values are still alive.
+    let pool = StringPool::named("registry-shared");
+    pool.register();
+    let shared = pool.get("hello");
+    let second = pool.get("world");
+    let info = find("registry-shared").unwrap();
+    assert_eq!(info.kind, Kind::Shared);
+    assert_eq!(info.len, 2);
+    drop(second);
+    assert_eq!(find("registry-shared").unwrap().len, 1);
+    drop(pool);
+    assert_eq!(find("registry-shared").unwrap().len, 1);
+    drop(shared);
+    assert!(find("registry-shared").is_none());
+
+    // Sharded pools are listed as a single pool.
+    let sharded_values = (0..8)
+        .map(|index| SHARDED.get(format!("registry-{index}")))
+        .collect::<Vec<_>>();
+    SHARDED.register();
+    let info = find("registry-sharded").unwrap();
+    assert_eq!(info.kind, Kind::ShardedGlobal);
+    assert_eq!(info.len, 8);
+    assert_eq!(info.memory, SHARDED.memory_usage());
+    drop(sharded_values);
+    let pool = ShardedSharedPool::<String>::named("registry-sharded-shared");
+    pool.register();
+    let _value = pool.get("hello");
+    let info = find("registry-sharded-shared").unwrap();
+    assert_eq!(info.kind, Kind::ShardedShared);
+    assert_eq!(info.len, 1);
+
+    // Unnamed pools are registered without a name.
+    UNNAMED.register();
+    let path = UNNAMED.get(PathBuf::from("registry-unnamed"));
+    assert!(registry::pools().iter().any(|info| info.name.is_none()
+        && info.kind == Kind::Global
+        && info.value_type == std::any::type_name::<PathBuf>()
+        && info.len == 1));
+    drop(path);
+    let unnamed = SharedPool::<Vec<u8>>::default();
+    unnamed.register();
+    let _value = unnamed.get(&b"hello"[..]);
+    assert!(registry::pools().iter().any(|info| info.name.is_none()
+        && info.kind == Kind::Shared
+        && info.value_type == std::any::type_name::<Vec<u8>>()));
+
+    // Pools whose types can't be registered can still be created.
+    let local = SharedPool::<String, LocalState>::with_hasher(LocalState::default());
+    assert_eq!(&**local.get("local"), "local");
+}
+
+#[test]
+fn leak_report() {
+    static LEAKS: GlobalPool<String> = GlobalPool::named("leaks");
+    static STATIC: StaticPooledString = LEAKS.get_static("static");
+
+    assert!(LEAKS.leak_report().is_empty());
+    assert_eq!(*STATIC, "static");
+    let _permanent = LEAKS.symbol("permanent");
+    LEAKS.leak_report().assert_empty();
+
+    let first = LEAKS.get("first");
+    let second = LEAKS.get("second");
+    let second_clone = second.clone();
+    // A reference in addition to the static's reference is reported.
+    let static_clone = STATIC.get().clone();
+
+    let report = LEAKS.leak_report();
+    let entries = report
+        .entries()
+        .iter()
+        .map(|entry| (&*entry.value, entry.strong_count))
+        .collect::<Vec<_>>();
+    assert_eq!(entries, [("static", 1), ("first", 1), ("second", 2)]);
+    assert_eq!(
+        report.to_string(),
+        "3 values remain in pool \"leaks\"\n  \"static\" (index 0, 1 references)\n  \
+         \"first\" (index 2, 1 references)\n  \"second\" (index 3, 2 references)"
+    );
+    let unexpected = report.clone().excluding(&["static", "second"]);
+    assert_eq!(unexpected.entries().len(), 1);
+    let message = panic::catch_unwind(|| unexpected.assert_empty())
+        .unwrap_err()
+        .downcast::<String>()
+        .unwrap();
+    assert!(message.contains("\"first\""));
+    report
+        .excluding(&["static", "first", "second"])
+        .assert_empty();
+
+    // Values whose release was deferred are collected before reporting.
+    LEAKS.set_release_mode(ReleaseMode::Deferred { threshold: 16 });
+    drop((first, second, second_clone, static_clone));
+    LEAKS.leak_report().assert_empty();
+    assert_eq!(LEAKS.len(), 2);
+    LEAKS.set_release_mode(ReleaseMode::Immediate);
+}
+
+#[test]
+#[cfg(feature = "track-holders")]
+fn holders() {
+    use std::backtrace::BacktraceStatus;
+
+    static SHARDED: ShardedGlobalPool<String> = ShardedGlobalPool::new();
+
+    let pool = StringPool::default();
+    assert!(pool.holders_of("hello").is_empty());
+
+    let hello = pool.get("hello");
+    let holders = pool.holders_of("hello");
+    assert_eq!(holders.len(), 1);
+    assert_eq!(holders[0].status(), BacktraceStatus::Captured);
+
+    let clone = hello.clone();
+    let existing = pool.get_existing("hello").unwrap();
+    let resolved = pool.resolve(hello.symbol()).unwrap();
+    assert_eq!(pool.holders_of("hello").len(), 4);
+    drop((clone, existing));
+    assert_eq!(pool.holders_of("hello").len(), 2);
+    drop(resolved);
+    let remaining = pool.holders_of("hello");
+    assert_eq!(remaining.len(), 1);
+    assert!(Arc::ptr_eq(&remaining[0], &holders[0]));
+    drop(hello);
+    assert!(pool.holders_of("hello").is_empty());
+
+    let sharded = SHARDED.get("hello");
+    assert_eq!(SHARDED.holders_of("hello").len(), 1);
+    drop(sharded);
+    assert!(SHARDED.holders_of("hello").is_empty());
+}
+
+#[test]
+fn deferred_releases_collected_when_pool_dropped() {
+    let pool = StringPool::default();
+    pool.set_release_mode(ReleaseMode::Deferred { threshold: 100 });
+    let value = pool.get("queued");
+    let weak = Pooled::downgrade(&value);
+    let held = pool.get("held");
+    let clone = pool.clone();
+    drop(value);
+    drop(pool);
+    // A handle remains, so the queued value is still allocated.
+    assert_eq!(clone.len(), 2);
+    drop(clone);
+    assert!(weak.upgrade().is_none());
+
+    // Values held when the pool is dropped are released immediately.
+    let weak = Pooled::downgrade(&held);
+    drop(held);
+    assert!(weak.upgrade().is_none());
+}